@@ -0,0 +1,166 @@
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use sea_migrations::SchemaDiff;
+use sea_orm::DbConn;
+
+// MOD_FILE_HEADER is the start of the `mod.rs` file created in a migrations directory which doesn't have one yet.
+const MOD_FILE_HEADER: &str = "// Migrations are added to this file by `sea-migrations generate`. Remember to add new migrations to the list passed to the Migrator.\n\n";
+
+/// migration_name will return the name of a new migration created at a given time from a description of what it does.
+/// The name is `M` followed by the UTC timestamp and the description in CamelCase so migrations sort in the order they were created.
+///
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use sea_migrations_cli::migration_name;
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1609466522); // 2021-01-01 02:02:02 UTC
+/// assert_eq!(migration_name("create bakery", time).unwrap(), "M20210101020202CreateBakery");
+/// assert_eq!(migration_name("add cake.price_2", time).unwrap(), "M20210101020202AddCakePrice2");
+/// assert!(migration_name("!!!", time).is_err());
+/// ```
+pub fn migration_name(description: &str, time: SystemTime) -> io::Result<String> {
+    let description = description
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let (first, rest) = word.split_at(1);
+            first.to_ascii_uppercase() + rest
+        })
+        .collect::<String>();
+    if description.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "The description of a migration must contain at least one letter or number!",
+        ));
+    }
+
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?
+        .as_secs();
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let secs_of_day = secs % 86400;
+
+    Ok(format!(
+        "M{:04}{:02}{:02}{:02}{:02}{:02}{}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        description
+    ))
+}
+
+/// generate_migration will create a new Rust file in a directory containing a migration with stub `up` and `down` functions and add it to the `mod.rs` file in the directory.
+/// The `mod.rs` file is created if it doesn't exist. The path of the new file is returned.
+pub fn generate_migration<P: AsRef<Path>>(dir: P, description: &str) -> io::Result<PathBuf> {
+    let name = migration_name(description, SystemTime::now())?;
+    write_migration(dir.as_ref(), &name, &migration_source(&name))
+}
+
+/// autogenerate_migration will create a new Rust file in a directory containing a migration which updates the database to match the Entities of a SchemaDiff and add it to the `mod.rs` file in the directory.
+/// None is returned and no file is created if the database already matches the Entities. Review the migration before running it as some changes are left as `TODO` comments.
+pub async fn autogenerate_migration<P: AsRef<Path>>(
+    dir: P,
+    description: &str,
+    db: &DbConn,
+    diff: &SchemaDiff,
+) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let name = migration_name(description, SystemTime::now())?;
+    match diff.generate_migration(db, &name).await? {
+        Some(source) => Ok(Some(write_migration(dir.as_ref(), &name, &source)?)),
+        None => Ok(None),
+    }
+}
+
+// write_migration creates the Rust file for a migration and adds it to the `mod.rs` file in the directory.
+fn write_migration(dir: &Path, name: &str, source: &str) -> io::Result<PathBuf> {
+    let module = module_name(name);
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.rs", module));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?
+        .write_all(source.as_bytes())?;
+
+    let mod_path = dir.join("mod.rs");
+    let mod_file = match fs::read_to_string(&mod_path) {
+        Ok(mod_file) => mod_file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => MOD_FILE_HEADER.to_string(),
+        Err(err) => return Err(err),
+    };
+    let separator = if mod_file.is_empty() || mod_file.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    fs::write(
+        &mod_path,
+        format!(
+            "{}{}mod {};\npub use {}::{};\n",
+            mod_file, separator, module, module, name
+        ),
+    )?;
+
+    Ok(path)
+}
+
+// module_name converts the name of a migration into the name of it's Rust module (eg. `M20210101020202CreateBakery` becomes `m20210101020202_create_bakery`).
+fn module_name(name: &str) -> String {
+    let mut module = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i != 0 {
+            module.push('_');
+        }
+        module.push(c.to_ascii_lowercase());
+    }
+    module
+}
+
+// migration_source returns the contents of the Rust file for a new migration.
+fn migration_source(name: &str) -> String {
+    format!(
+        r#"use async_trait::async_trait;
+use sea_migrations::{{Migration, MigrationManager, MigratorTrait}};
+use sea_orm::DbErr;
+
+#[derive(Migration)]
+pub struct {name};
+
+#[async_trait]
+impl MigratorTrait for {name} {{
+    async fn up(&self, _mg: &MigrationManager) -> Result<(), DbErr> {{
+        todo!("Write the up migration for {name}")
+    }}
+    async fn down(&self, _mg: &MigrationManager) -> Result<(), DbErr> {{
+        todo!("Write the down migration for {name}")
+    }}
+}}
+"#,
+        name = name
+    )
+}
+
+// civil_from_days converts a number of days since 1970-01-01 into a (year, month, day) date. This is Howard Hinnant's algorithm from http://howardhinnant.github.io/date_algorithms.html#civil_from_days.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,357 @@
+#![deny(missing_docs)]
+
+//! Command line interface for [sea-migrations](https://github.com/oscartbeaumont/sea-migrations).
+//!
+//! The `sea-migrations` binary runs the SQL migrations in the directory set by the `MIGRATIONS_DIR` environment variable (`migrations` by default). Projects with migrations written in Rust can build their own binary which embeds them using the `main!` macro:
+//!
+//! ```rust,no_run
+//! // src/bin/migrate.rs
+//! sea_migrations_cli::main!(/* models::M20210101020202DoAThing, models::M20210105020202DoAThingAgain */);
+//! ```
+//!
+//! The database is connected to using the `DATABASE_URL` environment variable. Run the binary with `help` to list the commands.
+
+use std::{env, error::Error, fmt, process};
+
+use sea_migrations::{MigrationState, MigrationStatus, Migrator, MigratorTrait, SchemaDiff};
+use sea_orm::{Database, DbConn};
+
+pub use generate::*;
+pub use sea_migrations;
+
+mod generate;
+
+// DEFAULT_GENERATE_DIR is the directory new migrations are created in by the `generate` command when no directory is given.
+const DEFAULT_GENERATE_DIR: &str = "src/migrations";
+
+/// USAGE is the help text printed by the `help` command and when the arguments are invalid.
+pub const USAGE: &str = "Usage: sea-migrations <command>
+
+Commands:
+  up            Run all pending migrations
+  down [n]      Undo the last n applied migrations (default 1)
+  status        List every migration and whether it has been applied
+  redo          Undo the last applied migration and run it again
+  fresh         Drop everything in the database and run all migrations
+  reset         Undo every applied migration
+  plan          List the migrations `up` would run without running them
+  force-unlock  Remove a lock left behind by a killed migration process
+  generate <description> [dir]
+                Create a new Rust migration in dir (default src/migrations) and add it to dir/mod.rs
+  autogenerate <description> [dir]
+                Like generate but the migration updates the database to match the entities of the CLI
+  help          Print this message
+
+Environment:
+  DATABASE_URL    The database to connect to (required by every command except generate)
+  MIGRATIONS_DIR  The directory of SQL migrations used by the sea-migrations binary (default: migrations)";
+
+/// Command is a subcommand of the CLI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Up runs all pending migrations.
+    Up,
+    /// Down undoes the given number of applied migrations.
+    Down(usize),
+    /// Status lists every migration and whether it has been applied.
+    Status,
+    /// Redo undoes the last applied migration and runs it again.
+    Redo,
+    /// Fresh drops everything in the database and runs all migrations.
+    Fresh,
+    /// Reset undoes every applied migration.
+    Reset,
+    /// Plan lists the migrations `Up` would run.
+    Plan,
+    /// ForceUnlock removes the lock on the migrations table.
+    ForceUnlock,
+    /// Generate creates a new Rust migration in a directory.
+    Generate {
+        /// description is what the migration does. It is used in the name of the migration.
+        description: String,
+        /// dir is the directory the migration is created in.
+        dir: String,
+    },
+    /// Autogenerate creates a new Rust migration in a directory which updates the database to match the Entities given to the CLI.
+    Autogenerate {
+        /// description is what the migration does. It is used in the name of the migration.
+        description: String,
+        /// dir is the directory the migration is created in.
+        dir: String,
+    },
+    /// Help prints the usage of the CLI.
+    Help,
+}
+
+impl Command {
+    /// parse will parse the command from the command line arguments excluding the name of the binary.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, CliError> {
+        let mut args = args.into_iter();
+        let command = match args.next().as_deref() {
+            Some("up") => Self::Up,
+            Some("down") => match args.next() {
+                Some(steps) => Self::Down(steps.parse().map_err(|_| {
+                    CliError(format!("'{}' is not a valid number of migrations!", steps))
+                })?),
+                None => Self::Down(1),
+            },
+            Some("status") => Self::Status,
+            Some("redo") => Self::Redo,
+            Some("fresh") => Self::Fresh,
+            Some("reset") => Self::Reset,
+            Some("plan") => Self::Plan,
+            Some("force-unlock") => Self::ForceUnlock,
+            Some("generate") => Self::Generate {
+                description: args.next().ok_or_else(|| {
+                    CliError("The generate command requires a description of the migration!".into())
+                })?,
+                dir: args.next().unwrap_or_else(|| DEFAULT_GENERATE_DIR.into()),
+            },
+            Some("autogenerate") => Self::Autogenerate {
+                description: args.next().ok_or_else(|| {
+                    CliError(
+                        "The autogenerate command requires a description of the migration!".into(),
+                    )
+                })?,
+                dir: args.next().unwrap_or_else(|| DEFAULT_GENERATE_DIR.into()),
+            },
+            Some("help" | "--help" | "-h") | None => Self::Help,
+            Some(command) => return Err(CliError(format!("Unknown command '{}'!", command))),
+        };
+
+        match args.next() {
+            Some(arg) => Err(CliError(format!("Unexpected argument '{}'!", arg))),
+            None => Ok(command),
+        }
+    }
+}
+
+/// CliError is returned when the command line arguments or environment are invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError(pub String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for CliError {}
+
+/// main will run the CLI using the command line arguments of the process. It prints any error and exits the process with a non-zero exit code if the command fails.
+/// It is used by the `main!` macro. Call it directly if your migrations are not a list of values, for example when using `embed_migrations!`.
+pub fn main(migrations: Vec<Box<dyn MigratorTrait>>) {
+    main_with_schema(migrations, SchemaDiff::new())
+}
+
+/// main_with_schema will run the CLI like `main` but the `autogenerate` command compares the database with the Entities of the SchemaDiff.
+pub fn main_with_schema(migrations: Vec<Box<dyn MigratorTrait>>, schema: SchemaDiff) {
+    let command = match Command::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("Failed to start the async runtime: {}", err);
+            process::exit(1);
+        }
+    };
+    if let Err(err) = runtime.block_on(run_with_schema(command, migrations, &schema)) {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+/// run will connect to the database in the `DATABASE_URL` environment variable and run a command against it using the provided migrations.
+pub async fn run(
+    command: Command,
+    migrations: Vec<Box<dyn MigratorTrait>>,
+) -> Result<(), Box<dyn Error>> {
+    run_with_schema(command, migrations, &SchemaDiff::new()).await
+}
+
+/// run_with_schema will run a command like `run` but the `autogenerate` command compares the database with the Entities of the SchemaDiff.
+pub async fn run_with_schema(
+    command: Command,
+    mut migrations: Vec<Box<dyn MigratorTrait>>,
+    schema: &SchemaDiff,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Help => println!("{}", USAGE),
+        Command::Generate { description, dir } => {
+            let path = generate_migration(dir, &description)?;
+            println!("Created {}", path.display());
+        }
+        Command::Up => up(&connect().await?, &mut migrations).await?,
+        Command::Down(steps) => down(&connect().await?, &migrations, steps).await?,
+        Command::Status => {
+            let status = Migrator::status(&connect().await?, &migrations).await?;
+            if status.is_empty() {
+                println!("No migrations found.");
+            }
+            for migration in status {
+                let state = match migration.state {
+                    MigrationState::Applied => "applied",
+                    MigrationState::Pending => "pending",
+                    MigrationState::Missing => "missing",
+                };
+                println!("{:<8} {}", state, migration.name);
+            }
+        }
+        Command::Redo => match Migrator::redo(&connect().await?, &migrations).await? {
+            Some(name) => println!("Redid {}", name),
+            None => println!("No applied migrations."),
+        },
+        Command::Fresh => {
+            Migrator::fresh(&connect().await?, &mut migrations).await?;
+            println!(
+                "Dropped everything and ran {} migrations.",
+                migrations.len()
+            );
+        }
+        Command::Reset => down(&connect().await?, &migrations, usize::MAX).await?,
+        Command::Plan => {
+            let pending =
+                with_state(&connect().await?, &migrations, MigrationState::Pending).await?;
+            if pending.is_empty() {
+                println!("No pending migrations.");
+            }
+            for migration in pending {
+                println!("{}", migration.name);
+            }
+        }
+        Command::ForceUnlock => {
+            Migrator::force_unlock(&connect().await?).await?;
+            println!("Removed the lock on the migrations table.");
+        }
+        Command::Autogenerate { .. } if schema.is_empty() => {
+            return Err(CliError("No entities were provided to compare the database with! List them in the `sea_migrations_cli::main!` macro after a semicolon.".into()).into());
+        }
+        Command::Autogenerate { description, dir } => {
+            match autogenerate_migration(dir, &description, &connect().await?, schema).await? {
+                Some(path) => println!("Created {}", path.display()),
+                None => println!("The database already matches the entities."),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// connect connects to the database in the `DATABASE_URL` environment variable. It is only called by the commands which use the database so `help` and `generate` work without it.
+async fn connect() -> Result<DbConn, Box<dyn Error>> {
+    let url = env::var("DATABASE_URL").map_err(|_| {
+        CliError(
+            "The 'DATABASE_URL' environment variable must be set to the database to connect to!"
+                .into(),
+        )
+    })?;
+    Ok(Database::connect(&url).await?)
+}
+
+// up runs the pending migrations and prints the name of each one that was applied. The applied migrations are read again after running them as another process may have applied some of the pending migrations first.
+async fn up(
+    db: &DbConn,
+    migrations: &mut Vec<Box<dyn MigratorTrait>>,
+) -> Result<(), Box<dyn Error>> {
+    let before = applied_names(db, migrations).await?;
+    Migrator::run(db, migrations).await?;
+    let after = applied_names(db, migrations).await?;
+
+    let applied = after
+        .iter()
+        .filter(|name| !before.contains(name))
+        .collect::<Vec<_>>();
+    if applied.is_empty() {
+        println!("No pending migrations.");
+    }
+    for name in applied {
+        println!("Applied {}", name);
+    }
+    Ok(())
+}
+
+// down undoes the last `steps` applied migrations and prints the name of each one that was reverted. The applied migrations are read again after undoing them so only the migrations which were actually reverted are printed.
+async fn down(
+    db: &DbConn,
+    migrations: &[Box<dyn MigratorTrait>],
+    steps: usize,
+) -> Result<(), Box<dyn Error>> {
+    let before = applied_names(db, migrations).await?;
+    Migrator::down(db, migrations, steps).await?;
+    let after = applied_names(db, migrations).await?;
+
+    let reverted = before
+        .iter()
+        .rev()
+        .filter(|name| !after.contains(name))
+        .collect::<Vec<_>>();
+    if reverted.is_empty() {
+        println!("No applied migrations.");
+    }
+    for name in reverted {
+        println!("Reverted {}", name);
+    }
+    Ok(())
+}
+
+// applied_names returns the names of the migrations which have been applied to the database (including ones which are missing from the binary) in ascending order.
+async fn applied_names(
+    db: &DbConn,
+    migrations: &[Box<dyn MigratorTrait>],
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut names = Migrator::status(db, migrations)
+        .await?
+        .into_iter()
+        .filter(|migration| migration.state != MigrationState::Pending)
+        .map(|migration| migration.name)
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names)
+}
+
+// with_state returns the migrations which are in a given state.
+async fn with_state(
+    db: &DbConn,
+    migrations: &[Box<dyn MigratorTrait>],
+    state: MigrationState,
+) -> Result<Vec<MigrationStatus>, Box<dyn Error>> {
+    Ok(Migrator::status(db, migrations)
+        .await?
+        .into_iter()
+        .filter(|migration| migration.state == state)
+        .collect())
+}
+
+/// main creates the `main` function of a binary which runs the CLI on the provided migrations. This allows a project to build it's own migrations CLI in one line.
+/// Entities can be listed after a semicolon so the `autogenerate` command can create migrations for them.
+///
+/// ```rust,no_run
+/// // src/bin/migrate.rs
+/// sea_migrations_cli::main!(/* models::M20210101020202DoAThing, models::M20210105020202DoAThingAgain; models::customer::Entity */);
+/// ```
+#[macro_export]
+macro_rules! main {
+    ($($migration:expr),* ; $($entity:expr),* $(,)?) => {
+        fn main() {
+            $crate::main_with_schema(
+                ::std::vec![
+                    $(::std::boxed::Box::new($migration) as ::std::boxed::Box<dyn $crate::sea_migrations::MigratorTrait>),*
+                ],
+                $crate::sea_migrations::SchemaDiff::new()$(.entity($entity))*,
+            );
+        }
+    };
+    ($($migration:expr),* $(,)?) => {
+        fn main() {
+            $crate::main(::std::vec![
+                $(::std::boxed::Box::new($migration) as ::std::boxed::Box<dyn $crate::sea_migrations::MigratorTrait>),*
+            ]);
+        }
+    };
+}
@@ -0,0 +1,24 @@
+use std::{env, process};
+
+use sea_migrations::{MigratorTrait, SqlMigration};
+
+// MIGRATIONS_DIR is the directory the SQL migrations are loaded from when the `MIGRATIONS_DIR` environment variable isn't set.
+const MIGRATIONS_DIR: &str = "migrations";
+
+fn main() {
+    let dir = env::var("MIGRATIONS_DIR").unwrap_or_else(|_| MIGRATIONS_DIR.into());
+    let migrations = match SqlMigration::load_dir(&dir) {
+        Ok(migrations) => migrations,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+    };
+
+    sea_migrations_cli::main(
+        migrations
+            .into_iter()
+            .map(|migration| Box::new(migration) as Box<dyn MigratorTrait>)
+            .collect(),
+    );
+}
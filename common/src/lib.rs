@@ -0,0 +1,76 @@
+//! Code shared by the sea-migrations crate and it's derive macros. Don't use directly!
+
+/// SqlBackend is the database backend a SQL migration file is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SqlBackend {
+    /// Postgres is used for `<name>.postgres.up.sql` and `<name>.postgres.down.sql` files.
+    Postgres,
+    /// MySql is used for `<name>.mysql.up.sql` and `<name>.mysql.down.sql` files.
+    MySql,
+    /// Sqlite is used for `<name>.sqlite.up.sql` and `<name>.sqlite.down.sql` files.
+    Sqlite,
+}
+
+/// SqlFile is the parsed file name of a SQL migration file (eg. `M20210101020202DoAThing.postgres.up.sql`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SqlFile<'a> {
+    /// migration is the name of the migration the file belongs to.
+    pub migration: &'a str,
+    /// backend is the database backend the SQL is for. It is None if the SQL is used on every database backend.
+    pub backend: Option<SqlBackend>,
+    /// is_up is whether the file contains the up SQL (`.up.sql`) rather than the down SQL (`.down.sql`).
+    pub is_up: bool,
+}
+
+impl<'a> SqlFile<'a> {
+    /// parse will split the file name of a SQL migration file into it's parts. None is returned if the file isn't a SQL migration file.
+    pub fn parse(file_name: &'a str) -> Option<Self> {
+        let (stem, is_up) = match (
+            file_name.strip_suffix(".up.sql"),
+            file_name.strip_suffix(".down.sql"),
+        ) {
+            (Some(stem), _) => (stem, true),
+            (_, Some(stem)) => (stem, false),
+            _ => return None,
+        };
+
+        let (migration, backend) = match stem.rsplit_once('.') {
+            Some((migration, "postgres")) => (migration, Some(SqlBackend::Postgres)),
+            Some((migration, "mysql")) => (migration, Some(SqlBackend::MySql)),
+            Some((migration, "sqlite")) => (migration, Some(SqlBackend::Sqlite)),
+            _ => (stem, None),
+        };
+        Some(Self {
+            migration,
+            backend,
+            is_up,
+        })
+    }
+}
+
+/// check_sql_files will check that every migration in a set of SQL migration files has an up file. The error message for the first migration without one is returned. Files which aren't SQL migration files are ignored.
+pub fn check_sql_files<'a>(file_names: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+    let files = file_names
+        .into_iter()
+        .filter_map(SqlFile::parse)
+        .collect::<Vec<_>>();
+
+    let mut missing_up = files
+        .iter()
+        .filter(|file| {
+            !files
+                .iter()
+                .any(|other| other.is_up && other.migration == file.migration)
+        })
+        .map(|file| file.migration)
+        .collect::<Vec<_>>();
+    missing_up.sort_unstable();
+
+    match missing_up.first() {
+        Some(migration) => Err(format!(
+            "The migration '{}' has no '.up.sql' file!",
+            migration
+        )),
+        None => Ok(()),
+    }
+}
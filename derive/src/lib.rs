@@ -1,5 +1,11 @@
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, Data, DeriveInput};
+use sea_migrations_common::{check_sql_files, SqlBackend, SqlFile};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+};
+use syn::{parse_macro_input, Data, DeriveInput, LitStr};
 
 #[macro_use]
 extern crate quote;
@@ -16,7 +22,7 @@ pub fn derive_migrator_macro(input: TokenStream) -> TokenStream {
 
     let value = ident.to_string();
     quote! {
-        impl sea_migrations::MigrationName for #ident {
+        impl ::sea_migrations::MigrationName for #ident {
             fn name(&self) -> &'static str {
                 #value
             }
@@ -24,3 +30,88 @@ pub fn derive_migrator_macro(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// The embed_migrations macro embeds the SQL migrations in a directory into the binary. The directory is relative to the root of the crate (where it's Cargo.toml is).
+/// Each migration is a `<name>.up.sql` file and an optional `<name>.down.sql` file. SQL for a single database backend can be put in a `<name>.<backend>.up.sql` or `<name>.<backend>.down.sql` file where the backend is `postgres`, `mysql` or `sqlite`.
+/// The macro evaluates to a `Vec<Box<dyn MigratorTrait>>` containing a `SqlMigration` for each migration. A compile error is reported if a migration has no `.up.sql` file. Changes to the SQL files are picked up automatically but adding a new file requires the crate to be rebuilt.
+#[proc_macro]
+pub fn embed_migrations(input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(input as LitStr);
+    let path =
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join(dir.value());
+
+    let entries = match fs::read_dir(&path) {
+        Ok(entries) => entries,
+        Err(err) => {
+            return syn::Error::new(
+                dir.span(),
+                format!(
+                    "Failed to read the migrations directory '{}': {}",
+                    path.display(),
+                    err
+                ),
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+
+    let file_names = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_string()))
+        .collect::<Vec<_>>();
+    if let Err(err) = check_sql_files(file_names.iter().map(String::as_str)) {
+        return syn::Error::new(dir.span(), err).to_compile_error().into();
+    }
+
+    // migrations maps the name of each migration to the SQL files it's up and down SQL is read from. A BTreeMap is used so the generated code doesn't depend on the order of the directory listing.
+    let mut migrations = BTreeMap::<&str, (Vec<SqlFile>, Vec<SqlFile>)>::new();
+    let mut paths = HashMap::<SqlFile, String>::new();
+    for file_name in &file_names {
+        if let Some(file) = SqlFile::parse(file_name) {
+            let (up, down) = migrations.entry(file.migration).or_default();
+            if file.is_up { up } else { down }.push(file);
+            paths.insert(file, path.join(file_name).to_string_lossy().to_string());
+        }
+    }
+
+    // The SQL files are embedded using `include_str!` so the crate is rebuilt when they change.
+    let backend_sql = |files: &[SqlFile]| {
+        let variants = files.iter().map(|file| {
+            let method = format_ident!(
+                "{}",
+                match file.backend {
+                    Some(SqlBackend::Postgres) => "postgres",
+                    Some(SqlBackend::MySql) => "mysql",
+                    Some(SqlBackend::Sqlite) => "sqlite",
+                    None => "fallback",
+                }
+            );
+            let file = &paths[file];
+            quote! { .#method(include_str!(#file)) }
+        });
+        quote! { ::sea_migrations::BackendSql::new()#(#variants)* }
+    };
+    let migrations = migrations.iter().map(|(name, (up, down))| {
+        let up = backend_sql(up);
+        let down = if down.is_empty() {
+            quote! { ::std::option::Option::None }
+        } else {
+            let down = backend_sql(down);
+            quote! { ::std::option::Option::Some(#down) }
+        };
+        quote! {
+            ::std::boxed::Box::new(::sea_migrations::SqlMigration::with_backend_sql(#name, #up, #down))
+                as ::std::boxed::Box<dyn ::sea_migrations::MigratorTrait>
+        }
+    });
+
+    quote! {
+        {
+            let migrations: ::std::vec::Vec<::std::boxed::Box<dyn ::sea_migrations::MigratorTrait>> =
+                ::std::vec![#(#migrations),*];
+            migrations
+        }
+    }
+    .into()
+}
@@ -0,0 +1,6 @@
+CREATE TABLE bakery (
+    id INTEGER NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE INDEX idx_bakery_name ON bakery (name);
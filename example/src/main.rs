@@ -1,4 +1,4 @@
-use sea_migrations::Migrator;
+use sea_migrations::{embed_migrations, Migrator, MigratorTrait};
 use sea_orm::Database;
 
 mod models;
@@ -7,14 +7,13 @@ mod models;
 async fn main() -> Result<(), sea_orm::DbErr> {
     let db = Database::connect("sqlite://./test.db?mode=rwc").await?;
 
-    Migrator::run(
-        &db,
-        &mut vec![
-            Box::new(models::M20210101020202DoAThing),
-            Box::new(models::M20210105020202DoAThingAgain),
-        ],
-    )
-    .await?;
+    let mut migrations: Vec<Box<dyn MigratorTrait>> = vec![
+        Box::new(models::M20210101020202DoAThing),
+        Box::new(models::M20210105020202DoAThingAgain),
+    ];
+    migrations.extend(embed_migrations!("migrations/"));
+
+    Migrator::run(&db, &mut migrations).await?;
 
     Ok(())
 }
@@ -10,6 +10,7 @@ pub struct Model {
     pub notes: Option<String>,
 
     pub tax_info_id: i32,
+    pub some_value: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,11 +1,56 @@
 use async_trait::async_trait;
-use sea_migrations::{Migration, MigrationManager, MigratorTrait};
+use sea_migrations::{Migration, MigrationManager, MigratorTrait, TableSchema};
 use sea_orm::DbErr;
 
 pub mod customer;
-pub mod customer2;
 pub mod tax_info; // Customer -> Tax Info (1:1)
 
+// customer_v1 is a snapshot of the customer table before `some_value` was added to the Entity. It is the output of `TableSchema::from_entity(customer::Entity).to_rust_source()`, formatted with rustfmt, from when the first migration was written so the migration doesn't change when the Entity does.
+fn customer_v1() -> TableSchema {
+    sea_migrations::TableSchema {
+        name: "customer".into(),
+        columns: vec![
+            sea_migrations::ColumnSchema {
+                name: "id".into(),
+                col_type: sea_orm::ColumnType::Integer,
+                nullable: false,
+                default: None,
+                auto_increment: true,
+            },
+            sea_migrations::ColumnSchema {
+                name: "name".into(),
+                col_type: sea_orm::ColumnType::String(None),
+                nullable: false,
+                default: None,
+                auto_increment: false,
+            },
+            sea_migrations::ColumnSchema {
+                name: "notes".into(),
+                col_type: sea_orm::ColumnType::Text,
+                nullable: true,
+                default: None,
+                auto_increment: false,
+            },
+            sea_migrations::ColumnSchema {
+                name: "tax_info_id".into(),
+                col_type: sea_orm::ColumnType::Integer,
+                nullable: false,
+                default: None,
+                auto_increment: false,
+            },
+        ],
+        primary_key: vec!["id".into()],
+        foreign_keys: vec![sea_migrations::ForeignKeySchema {
+            columns: vec!["tax_info_id".into()],
+            referenced_table: "tax_info".into(),
+            referenced_columns: vec!["id".into()],
+            on_delete: Some(sea_orm::ForeignKeyAction::Cascade),
+            on_update: Some(sea_orm::ForeignKeyAction::Cascade),
+        }],
+        indexes: vec![],
+    }
+}
+
 #[derive(Migration)]
 pub struct M20210101020202DoAThing;
 
@@ -13,13 +58,13 @@ pub struct M20210101020202DoAThing;
 impl MigratorTrait for M20210101020202DoAThing {
     async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr> {
         println!("up: M20210101020202DoAThing");
-        mg.create_table(customer::Entity).await?;
+        mg.create_table_from_schema(&customer_v1()).await?;
         mg.create_table(tax_info::Entity).await?;
         Ok(())
     }
     async fn down(&self, mg: &MigrationManager) -> Result<(), DbErr> {
         println!("down: M20210101020202DoAThing");
-        mg.drop_table(customer::Entity).await?;
+        mg.drop_table_from_schema(&customer_v1()).await?;
         mg.drop_table(tax_info::Entity).await?;
         Ok(())
     }
@@ -32,7 +77,7 @@ pub struct M20210105020202DoAThingAgain;
 impl MigratorTrait for M20210105020202DoAThingAgain {
     async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr> {
         println!("up: M20210105020202DoAThingAgain");
-        mg.add_column(customer2::Entity, customer2::Column::SomeValue)
+        mg.add_column(customer::Entity, customer::Column::SomeValue)
             .await?;
 
         // If you need to do anything special you have the full power of sea_query by using the DB instance at `mg.db`
@@ -41,7 +86,7 @@ impl MigratorTrait for M20210105020202DoAThingAgain {
     }
     async fn down(&self, mg: &MigrationManager) -> Result<(), DbErr> {
         println!("down: M20210105020202DoAThingAgain");
-        mg.drop_column(customer2::Entity, customer2::Column::SomeValue)
+        mg.drop_column(customer::Entity, customer::Column::SomeValue)
             .await?;
         Ok(())
     }
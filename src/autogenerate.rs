@@ -0,0 +1,284 @@
+use sea_orm::{DbConn, DbErr};
+
+use crate::{
+    schema_diff::{EntityPaths, ExpectedEntity},
+    IntrospectedTable, MigratorOptions, SchemaDiff, SchemaDifference,
+};
+
+impl SchemaDiff {
+    /// generate_migration will compare the Entities with the database and return the source code of a Rust migration called `name` which updates the database to match them. None is returned if the database already matches.
+    /// The `up` function calls `create_table`, `add_column`, `alter_column`, etc and the `down` function reverses them. Differences which can't be fixed automatically (eg. a column which was removed from an Entity) are left as `TODO` comments so review the migration before running it. A new non-null column is added using `add_column_with_default` with a `todo!()` in place of the value for the existing rows so the migration won't compile until one is picked.
+    /// The Entities are referenced using paths starting with `crate::` so the migration must be in the same crate as them. Add the Entities in the order their tables must be created (referenced tables first).
+    ///
+    /// ```rust
+    /// use sea_migrations::SchemaDiff;
+    /// use sea_orm::{entity::prelude::*, ConnectionTrait, Database, Statement};
+    ///
+    /// #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    /// #[sea_orm(table_name = "cake")]
+    /// pub struct Model {
+    ///     #[sea_orm(primary_key)]
+    ///     pub id: i32,
+    ///     pub name: String,
+    /// }
+    ///
+    /// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    /// pub enum Relation {}
+    ///
+    /// impl ActiveModelBehavior for ActiveModel {}
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///
+    ///     let source = SchemaDiff::new()
+    ///         .entity(Entity)
+    ///         .generate_migration(&db, "M20210101020202CreateCake")
+    ///         .await?
+    ///         .unwrap();
+    ///     assert!(source.contains("mg.create_table("));
+    ///     assert!(source.contains("mg.drop_table("));
+    ///
+    ///     // The existing rows need a value for a new non-null column so the migration leaves it to be filled in.
+    ///     db.execute(Statement::from_string(db.get_database_backend(), "CREATE TABLE cake (id INTEGER PRIMARY KEY AUTOINCREMENT)".into())).await?;
+    ///     let source = SchemaDiff::new()
+    ///         .entity(Entity)
+    ///         .generate_migration(&db, "M20210102020202AddCakeName")
+    ///         .await?
+    ///         .unwrap();
+    ///     assert!(source.contains("mg.add_column_with_default("));
+    ///     assert!(source.contains("// TODO: Replace `todo!()`"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn generate_migration(
+        &self,
+        db: &DbConn,
+        name: &str,
+    ) -> Result<Option<String>, DbErr> {
+        self.generate_migration_with_options(db, name, &MigratorOptions::default())
+            .await
+    }
+
+    /// generate_migration_with_options will return the source code of a Rust migration which updates the database to match the Entities using the provided options. Refer to `generate_migration` for more information.
+    pub async fn generate_migration_with_options(
+        &self,
+        db: &DbConn,
+        name: &str,
+        options: &MigratorOptions,
+    ) -> Result<Option<String>, DbErr> {
+        let mut up = Vec::new();
+        let mut down = Vec::new();
+        for (entity, expected, differences) in self.diff_entities(db, options).await? {
+            for (up_stmt, down_stmt) in migration_steps(entity, &expected, &differences, options) {
+                up.push(up_stmt);
+                down.extend(down_stmt);
+            }
+        }
+
+        if up.is_empty() {
+            return Ok(None);
+        }
+        // The changes are undone in the opposite order to how they were made.
+        down.reverse();
+
+        Ok(Some(migration_source(name, &up, &down)))
+    }
+}
+
+// migration_steps converts the differences between the table expected by an Entity and the database into the statements of the `up` function and the statements of the `down` function which undo them.
+fn migration_steps(
+    entity: &ExpectedEntity,
+    expected: &IntrospectedTable,
+    differences: &[SchemaDifference],
+    options: &MigratorOptions,
+) -> Vec<(String, Option<String>)> {
+    let paths = &entity.paths;
+    let e = &paths.entity;
+    let mut steps = Vec::new();
+    let mut altered_columns = Vec::new();
+
+    for difference in differences {
+        let step = match difference {
+            SchemaDifference::MissingTable { .. } => (
+                format!("mg.create_table({}).await?;", e),
+                Some(format!("mg.drop_table({}).await?;", e)),
+            ),
+            SchemaDifference::MissingColumn { table, column } => {
+                let c = column_path(paths, column);
+                let nullable = expected.columns.iter().any(|expected_column| {
+                    expected_column.name == *column && expected_column.nullable
+                });
+                // A non-null column can't be added to a table containing rows without a value to fill them in with so the user must pick one.
+                let up = if nullable {
+                    format!("mg.add_column({}, {}).await?;", e, c)
+                } else {
+                    steps.push((
+                        format!(
+                            "// TODO: Replace `todo!()` with the value the existing rows get for the new non-null column '{}.{}'.",
+                            table, column
+                        ),
+                        None,
+                    ));
+                    format!(
+                        "mg.add_column_with_default({}, {}, sea_migrations::ColumnDefault::value(todo!())).await?;",
+                        e, c
+                    )
+                };
+                (up, Some(format!("mg.drop_column({}, {}).await?;", e, c)))
+            }
+            SchemaDifference::ExtraColumn { .. } => (
+                format!(
+                    "// TODO: {}. Drop it if it is no longer needed.",
+                    difference
+                ),
+                None,
+            ),
+            SchemaDifference::ColumnType { table, column, .. }
+            | SchemaDifference::Nullability { table, column, .. } => {
+                // A column with a different type and nullability is only altered once.
+                if altered_columns.contains(&column) {
+                    continue;
+                }
+                altered_columns.push(column);
+
+                (
+                    format!(
+                        "mg.alter_column({}, {}).await?;",
+                        e,
+                        column_path(paths, column)
+                    ),
+                    Some(format!(
+                        "// TODO: Restore the previous definition of '{}.{}'.",
+                        table, column
+                    )),
+                )
+            }
+            SchemaDifference::MissingForeignKey {
+                columns,
+                referenced_table,
+                referenced_columns,
+                ..
+            } => {
+                let key = (
+                    columns.clone(),
+                    referenced_table.clone(),
+                    referenced_columns.clone(),
+                );
+                match paths.relations.iter().find(|(k, _)| *k == key) {
+                    Some((_, relation)) => (
+                        format!("mg.add_foreign_key({}, {}).await?;", e, relation),
+                        Some(format!("mg.drop_foreign_key({}, {}).await?;", e, relation)),
+                    ),
+                    None => (format!("// TODO: {}.", difference), None),
+                }
+            }
+            SchemaDifference::ExtraForeignKey {
+                table,
+                name,
+                columns,
+                referenced_table,
+                ..
+            } => match name {
+                Some(name) => (
+                    format!("mg.drop_foreign_key_by_name({}, {:?}).await?;", e, name),
+                    Some(format!(
+                        "// TODO: Recreate the foreign key {:?} from '{}' ({}) to '{}'.",
+                        name,
+                        table,
+                        columns.join(", "),
+                        referenced_table
+                    )),
+                ),
+                None => (
+                    format!(
+                        "// TODO: {}. Drop it if it is no longer needed.",
+                        difference
+                    ),
+                    None,
+                ),
+            },
+            SchemaDifference::MissingIndex {
+                table,
+                columns,
+                unique: true,
+            } => {
+                let column_names = columns.iter().map(String::as_str).collect::<Vec<_>>();
+                (
+                    format!(
+                        "mg.add_unique_constraint({}, [{}]).await?;",
+                        e,
+                        columns
+                            .iter()
+                            .map(|column| column_path(paths, column))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    Some(format!(
+                        "mg.drop_constraint({}, {:?}).await?;",
+                        e,
+                        options.naming_convention.unique(table, &column_names)
+                    )),
+                )
+            }
+            SchemaDifference::MissingIndex { .. } => (format!("// TODO: {}.", difference), None),
+        };
+        steps.push(step);
+    }
+
+    steps
+}
+
+// column_path returns the path of the Column variant for a column.
+fn column_path(paths: &EntityPaths, column: &str) -> String {
+    paths
+        .columns
+        .iter()
+        .find(|(name, _)| name == column)
+        .map(|(_, path)| path.clone())
+        .unwrap_or_else(|| format!("/* unknown column {:?} */", column))
+}
+
+// migration_source returns the source code of a migration with the statements of it's `up` and `down` functions.
+fn migration_source(name: &str, up: &[String], down: &[String]) -> String {
+    let indent = |stmts: &[String]| {
+        stmts
+            .iter()
+            .map(|stmt| format!("        {}\n", stmt))
+            .collect::<String>()
+    };
+    // The MigrationManager is unused when a function only contains TODO comments.
+    let mg = |stmts: &[String]| {
+        if stmts.iter().all(|stmt| stmt.starts_with("//")) {
+            "_mg"
+        } else {
+            "mg"
+        }
+    };
+
+    format!(
+        r#"use async_trait::async_trait;
+use sea_migrations::{{Migration, MigrationManager, MigratorTrait}};
+use sea_orm::DbErr;
+
+#[derive(Migration)]
+pub struct {name};
+
+#[async_trait]
+impl MigratorTrait for {name} {{
+    async fn up(&self, {up_mg}: &MigrationManager) -> Result<(), DbErr> {{
+{up}        Ok(())
+    }}
+    async fn down(&self, {down_mg}: &MigrationManager) -> Result<(), DbErr> {{
+{down}        Ok(())
+    }}
+}}
+"#,
+        name = name,
+        up_mg = mg(up),
+        up = indent(up),
+        down_mg = mg(down),
+        down = indent(down),
+    )
+}
@@ -0,0 +1,79 @@
+use sea_orm::{DbBackend, DbErr};
+
+/// BackendSql holds a variant of some SQL for each database backend. It is used when the SQL required differs between databases such as in the body of a trigger.
+///
+/// ```rust
+/// use sea_orm::DbBackend;
+/// use sea_migrations::BackendSql;
+///
+/// let sql = BackendSql::new()
+///     .postgres("CREATE INDEX idx_cake_name ON cake USING GIN (to_tsvector('english', name))")
+///     .mysql("CREATE FULLTEXT INDEX idx_cake_name ON cake (name)");
+///
+/// assert!(sql.get(DbBackend::MySql).is_ok());
+/// assert!(sql.get(DbBackend::Sqlite).is_err()); // No variant was provided for SQLite.
+/// assert_eq!(BackendSql::all("SELECT 1").get(DbBackend::Sqlite).unwrap(), "SELECT 1");
+///
+/// // The fallback is used for the backends without their own variant.
+/// let sql = BackendSql::new().fallback("SELECT 1").sqlite("SELECT 2");
+/// assert_eq!(sql.get(DbBackend::Postgres).unwrap(), "SELECT 1");
+/// assert_eq!(sql.get(DbBackend::Sqlite).unwrap(), "SELECT 2");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackendSql {
+    fallback: Option<String>,
+    postgres: Option<String>,
+    mysql: Option<String>,
+    sqlite: Option<String>,
+}
+
+impl BackendSql {
+    /// new creates a BackendSql without any variants. Add variants using the `postgres`, `mysql` and `sqlite` methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// all creates a BackendSql which uses the same SQL for every database backend.
+    pub fn all<S: Into<String>>(sql: S) -> Self {
+        Self::new().fallback(sql)
+    }
+
+    /// fallback sets the SQL used on the database backends which don't have their own variant.
+    pub fn fallback<S: Into<String>>(mut self, sql: S) -> Self {
+        self.fallback = Some(sql.into());
+        self
+    }
+
+    /// postgres sets the SQL used on Postgres.
+    pub fn postgres<S: Into<String>>(mut self, sql: S) -> Self {
+        self.postgres = Some(sql.into());
+        self
+    }
+
+    /// mysql sets the SQL used on MySQL.
+    pub fn mysql<S: Into<String>>(mut self, sql: S) -> Self {
+        self.mysql = Some(sql.into());
+        self
+    }
+
+    /// sqlite sets the SQL used on SQLite.
+    pub fn sqlite<S: Into<String>>(mut self, sql: S) -> Self {
+        self.sqlite = Some(sql.into());
+        self
+    }
+
+    /// get returns the SQL for a database backend or the fallback if the backend has no variant. An error is returned if neither was provided.
+    pub fn get(&self, backend: DbBackend) -> Result<&str, DbErr> {
+        let sql = match backend {
+            DbBackend::Postgres => &self.postgres,
+            DbBackend::MySql => &self.mysql,
+            DbBackend::Sqlite => &self.sqlite,
+        };
+        sql.as_deref().or(self.fallback.as_deref()).ok_or_else(|| {
+            DbErr::Custom(format!(
+                "No SQL was provided for the {:?} database backend!",
+                backend
+            ))
+        })
+    }
+}
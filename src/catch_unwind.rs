@@ -0,0 +1,49 @@
+use std::{
+    any::Any,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use sea_orm::DbErr;
+
+// CatchUnwind is a future which converts a panic while polling the inner future into a DbErr.
+// It is used to wrap the user's `up` and `down` functions so a panic inside a migration can't skip unlocking the migrations table.
+pub(crate) struct CatchUnwind<F> {
+    inner: F,
+}
+
+impl<F, T> Future for CatchUnwind<F>
+where
+    F: Future<Output = Result<T, DbErr>> + Unpin,
+{
+    type Output = Result<T, DbErr>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &mut self.inner;
+        match panic::catch_unwind(AssertUnwindSafe(|| Pin::new(inner).poll(cx))) {
+            Ok(poll) => poll,
+            Err(payload) => Poll::Ready(Err(DbErr::Custom(format!(
+                "Migration panicked: {}",
+                panic_message(&*payload)
+            )))),
+        }
+    }
+}
+
+// catch_unwind wraps a future so that any panic while it is being polled is returned as a DbErr.
+pub(crate) fn catch_unwind<F>(future: F) -> CatchUnwind<F> {
+    CatchUnwind { inner: future }
+}
+
+// panic_message extracts the message from a panic payload. Panics created with `panic!` contain either a `&str` or `String`.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
+}
@@ -0,0 +1,57 @@
+use sea_orm::{
+    sea_query::{
+        ColumnDef, MysqlQueryBuilder, PostgresQueryBuilder, QueryBuilder, SqliteQueryBuilder,
+    },
+    DbBackend, Value,
+};
+
+/// ColumnDefault is the default value of a database column. It is used to fill in the column for existing rows when a non-null column is added to a table.
+/// SeaORM Entities can't describe the default value of a column so defaults can't be sourced from Entity attributes. The default must be passed to the helper creating or altering the column (eg. `MigrationManager::add_column_with_default`) instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnDefault {
+    /// Value is a literal value such as a number or string.
+    Value(Value),
+    /// Expr is a SQL expression such as `CURRENT_TIMESTAMP`. It is inserted into the SQL statement as is so it must be valid for your database backend.
+    Expr(String),
+}
+
+impl ColumnDefault {
+    /// value creates a ColumnDefault from a literal value.
+    pub fn value<V: Into<Value>>(value: V) -> Self {
+        Self::Value(value.into())
+    }
+
+    /// expr creates a ColumnDefault from a SQL expression.
+    pub fn expr<S: Into<String>>(expr: S) -> Self {
+        Self::Expr(expr.into())
+    }
+
+    /// current_timestamp creates a ColumnDefault which sets the column to the time the row was created.
+    pub fn current_timestamp() -> Self {
+        Self::expr("CURRENT_TIMESTAMP")
+    }
+
+    // apply sets the default value on a sea_query ColumnDef.
+    pub(crate) fn apply(&self, column_def: &mut ColumnDef) {
+        match self {
+            Self::Value(value) => {
+                column_def.default(value.clone());
+            }
+            Self::Expr(expr) => {
+                column_def.extra(format!("DEFAULT {}", expr));
+            }
+        }
+    }
+
+    // to_sql returns the SQL for the default value on the database backend.
+    pub(crate) fn to_sql(&self, backend: DbBackend) -> String {
+        match self {
+            Self::Value(value) => match backend {
+                DbBackend::Postgres => PostgresQueryBuilder.value_to_string(value),
+                DbBackend::MySql => MysqlQueryBuilder.value_to_string(value),
+                DbBackend::Sqlite => SqliteQueryBuilder.value_to_string(value),
+            },
+            Self::Expr(expr) => expr.clone(),
+        }
+    }
+}
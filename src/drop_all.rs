@@ -0,0 +1,227 @@
+use sea_orm::{
+    sea_query::Alias, ConnectionTrait, DatabaseTransaction, DbBackend, DbConn, DbErr, Statement,
+};
+
+use crate::{migrations_table::MIGRATIONS_TABLE_NAME, seaorm_integration::quote_iden, sqlite};
+
+// drop_all drops every table, view, enum type and function in the database except for the migrations table. It is used to start again from an empty database.
+pub(crate) async fn drop_all(db: &DbConn) -> Result<(), DbErr> {
+    match db.get_database_backend() {
+        DbBackend::Postgres => drop_all_postgres(db).await,
+        DbBackend::MySql => drop_all_mysql(db).await,
+        DbBackend::Sqlite => drop_all_sqlite(db).await,
+    }
+}
+
+// drop_all_postgres drops everything in the current schema. Objects which belong to an extension are left for the extension to manage.
+async fn drop_all_postgres(db: &DbConn) -> Result<(), DbErr> {
+    let materialized_views = query_names(
+        db,
+        "SELECT matviewname AS name FROM pg_matviews WHERE schemaname = current_schema()",
+    )
+    .await?;
+    for view in materialized_views {
+        execute(
+            db,
+            format!(
+                "DROP MATERIALIZED VIEW IF EXISTS {} CASCADE",
+                quote_iden(DbBackend::Postgres, &Alias::new(&view))
+            ),
+        )
+        .await?;
+    }
+
+    let views = query_names(
+        db,
+        "SELECT viewname AS name FROM pg_views WHERE schemaname = current_schema()",
+    )
+    .await?;
+    for view in views {
+        execute(
+            db,
+            format!(
+                "DROP VIEW IF EXISTS {} CASCADE",
+                quote_iden(DbBackend::Postgres, &Alias::new(&view))
+            ),
+        )
+        .await?;
+    }
+
+    let tables = query_names(
+        db,
+        "SELECT tablename AS name FROM pg_tables WHERE schemaname = current_schema()",
+    )
+    .await?;
+    for table in tables
+        .into_iter()
+        .filter(|table| table != MIGRATIONS_TABLE_NAME)
+    {
+        execute(
+            db,
+            format!(
+                "DROP TABLE IF EXISTS {} CASCADE",
+                quote_iden(DbBackend::Postgres, &Alias::new(&table))
+            ),
+        )
+        .await?;
+    }
+
+    let types = query_names(
+        db,
+        "SELECT t.typname AS name FROM pg_type t JOIN pg_namespace n ON n.oid = t.typnamespace WHERE n.nspname = current_schema() AND t.typtype = 'e' AND NOT EXISTS (SELECT 1 FROM pg_depend d WHERE d.objid = t.oid AND d.deptype = 'e')",
+    )
+    .await?;
+    for name in types {
+        execute(
+            db,
+            format!(
+                "DROP TYPE IF EXISTS {} CASCADE",
+                quote_iden(DbBackend::Postgres, &Alias::new(&name))
+            ),
+        )
+        .await?;
+    }
+
+    // The signature is used as the name so overloaded functions can be dropped.
+    let functions = query_names(
+        db,
+        "SELECT p.oid::regprocedure::text AS name FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace WHERE n.nspname = current_schema() AND NOT EXISTS (SELECT 1 FROM pg_depend d WHERE d.objid = p.oid AND d.deptype = 'e')",
+    )
+    .await?;
+    for function in functions {
+        execute(db, format!("DROP FUNCTION IF EXISTS {} CASCADE", function)).await?;
+    }
+
+    Ok(())
+}
+
+// drop_all_mysql drops everything in the current database. Foreign key checks are disabled so the tables can be dropped in any order.
+async fn drop_all_mysql(db: &DbConn) -> Result<(), DbErr> {
+    // The transaction makes sure every statement runs on the same connection as `FOREIGN_KEY_CHECKS` is set per session.
+    let txn = db.begin().await?;
+    let backend = DbBackend::MySql;
+    txn.execute(Statement::from_string(
+        backend,
+        "SET FOREIGN_KEY_CHECKS = 0".into(),
+    ))
+    .await?;
+
+    let result = drop_all_mysql_objects(&txn).await;
+
+    // Foreign key checks are restored even if dropping failed so the connection isn't returned to the pool with them disabled.
+    let restore_result = txn
+        .execute(Statement::from_string(
+            backend,
+            "SET FOREIGN_KEY_CHECKS = 1".into(),
+        ))
+        .await;
+    result?;
+    restore_result?;
+    txn.commit().await
+}
+
+// drop_all_mysql_objects does the work of `drop_all_mysql` on the connection held by `txn` while foreign key checks are disabled.
+async fn drop_all_mysql_objects(txn: &DatabaseTransaction) -> Result<(), DbErr> {
+    let backend = DbBackend::MySql;
+    let tables = txn
+        .query_all(Statement::from_string(
+            backend,
+            "SELECT table_name AS name, table_type AS kind FROM information_schema.tables WHERE table_schema = DATABASE()".into(),
+        ))
+        .await?;
+    for table in tables {
+        let name: String = table.try_get("", "name")?;
+        let kind: String = table.try_get("", "kind")?;
+        if name == MIGRATIONS_TABLE_NAME {
+            continue;
+        }
+
+        let object = if kind == "VIEW" { "VIEW" } else { "TABLE" };
+        txn.execute(Statement::from_string(
+            backend,
+            format!(
+                "DROP {} IF EXISTS {}",
+                object,
+                quote_iden(backend, &Alias::new(&name))
+            ),
+        ))
+        .await?;
+    }
+
+    let routines = txn
+        .query_all(Statement::from_string(
+            backend,
+            "SELECT routine_name AS name, routine_type AS kind FROM information_schema.routines WHERE routine_schema = DATABASE()".into(),
+        ))
+        .await?;
+    for routine in routines {
+        let name: String = routine.try_get("", "name")?;
+        let kind: String = routine.try_get("", "kind")?;
+        txn.execute(Statement::from_string(
+            backend,
+            format!(
+                "DROP {} IF EXISTS {}",
+                kind,
+                quote_iden(backend, &Alias::new(&name))
+            ),
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
+
+// drop_all_sqlite drops every table and view in the database. Triggers and indexes are dropped with their tables.
+async fn drop_all_sqlite(db: &DbConn) -> Result<(), DbErr> {
+    // Foreign key enforcement is disabled so the tables can be dropped in any order. The PRAGMA only applies to a single connection so the tables are dropped on the connection it was run on.
+    sqlite::without_foreign_keys(db, |txn| {
+        Box::pin(async move {
+            let backend = DbBackend::Sqlite;
+            let objects = txn
+                .query_all(Statement::from_string(
+                    backend,
+                    "SELECT name, type AS kind FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%'".into(),
+                ))
+                .await?;
+            for object in objects {
+                let name: String = object.try_get("", "name")?;
+                let kind: String = object.try_get("", "kind")?;
+                if name == MIGRATIONS_TABLE_NAME {
+                    continue;
+                }
+
+                let object = if kind == "view" { "VIEW" } else { "TABLE" };
+                txn.execute(Statement::from_string(
+                    backend,
+                    format!(
+                        "DROP {} IF EXISTS {}",
+                        object,
+                        quote_iden(backend, &Alias::new(&name))
+                    ),
+                ))
+                .await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+// query_names runs a query which returns a `name` column and collects the names.
+async fn query_names(db: &DbConn, sql: &str) -> Result<Vec<String>, DbErr> {
+    db.query_all(Statement::from_string(
+        db.get_database_backend(),
+        sql.into(),
+    ))
+    .await?
+    .iter()
+    .map(|row| row.try_get("", "name"))
+    .collect()
+}
+
+// execute runs a raw SQL statement.
+async fn execute(db: &DbConn, sql: String) -> Result<(), DbErr> {
+    db.execute(Statement::from_string(db.get_database_backend(), sql))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+
+use sea_orm::DbErr;
+
+/// MigrationError is returned by the Migrator when running the database migrations fails.
+#[derive(Debug)]
+pub enum MigrationError {
+    /// Db is returned when a database operation run by sea_migrations itself (such as locking or updating the migrations table) fails.
+    Db(DbErr),
+    /// Up is returned when the `up` function of a migration fails. If the `down` function was run to undo the migration it succeeded.
+    Up {
+        /// migration is the name of the migration which failed.
+        migration: String,
+        /// error is the error returned by the `up` function.
+        error: DbErr,
+    },
+    /// Down is returned when the `up` function of a migration fails and the `down` function run to undo it also fails. Both errors are kept so the original cause is not lost.
+    Down {
+        /// migration is the name of the migration which failed.
+        migration: String,
+        /// up_error is the original error returned by the `up` function.
+        up_error: DbErr,
+        /// down_error is the error returned by the `down` function while undoing the migration.
+        down_error: DbErr,
+    },
+    /// Revert is returned when the `down` function of an applied migration fails while it is being reverted (eg. by `Migrator::down`).
+    Revert {
+        /// migration is the name of the migration which failed.
+        migration: String,
+        /// error is the error returned by the `down` function.
+        error: DbErr,
+    },
+}
+
+impl MigrationError {
+    /// migration returns the name of the migration that caused the error if the error was caused by a migration.
+    pub fn migration(&self) -> Option<&str> {
+        match self {
+            Self::Db(_) => None,
+            Self::Up { migration, .. }
+            | Self::Down { migration, .. }
+            | Self::Revert { migration, .. } => Some(migration),
+        }
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Db(err) => write!(f, "{}", err),
+            Self::Up { migration, error } => {
+                write!(f, "Migration '{}' failed: {}", migration, error)
+            }
+            Self::Down {
+                migration,
+                up_error,
+                down_error,
+            } => write!(
+                f,
+                "Migration '{}' failed: {}. Undoing the migration also failed: {}",
+                migration, up_error, down_error
+            ),
+            Self::Revert { migration, error } => {
+                write!(f, "Reverting migration '{}' failed: {}", migration, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Db(err) => Some(err),
+            Self::Up { error, .. } | Self::Revert { error, .. } => Some(error),
+            Self::Down { up_error, .. } => Some(up_error),
+        }
+    }
+}
+
+impl From<DbErr> for MigrationError {
+    fn from(err: DbErr) -> Self {
+        Self::Db(err)
+    }
+}
+
+// This allows the `?` operator to be used on the result of the Migrator inside functions which return a DbErr.
+impl From<MigrationError> for DbErr {
+    fn from(err: MigrationError) -> Self {
+        match err {
+            MigrationError::Db(err) => err,
+            err => DbErr::Custom(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use sea_orm::{ConnectionTrait, DbBackend, DbConn, DbErr, QueryResult, Statement};
+
+use crate::migrations_table::MIGRATIONS_TABLE_NAME;
+
+/// IntrospectedTable is the definition of a table read from a live database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntrospectedTable {
+    /// name is the name of the table.
+    pub name: String,
+    /// columns are the columns of the table in the order they are defined.
+    pub columns: Vec<IntrospectedColumn>,
+    /// foreign_keys are the foreign keys defined on the table.
+    pub foreign_keys: Vec<IntrospectedForeignKey>,
+    /// indexes are the indexes of the table not including the primary key.
+    pub indexes: Vec<IntrospectedIndex>,
+}
+
+/// IntrospectedColumn is the definition of a column read from a live database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntrospectedColumn {
+    /// name is the name of the column.
+    pub name: String,
+    /// data_type is the type of the column as reported by the database (eg. `character varying(255)` on Postgres or `varchar(255)` on MySQL).
+    pub data_type: String,
+    /// nullable is whether the column can contain NULL.
+    pub nullable: bool,
+}
+
+/// IntrospectedForeignKey is the definition of a foreign key read from a live database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntrospectedForeignKey {
+    /// name is the name of the foreign key constraint. SQLite doesn't report the names of foreign keys so it is always None on SQLite.
+    pub name: Option<String>,
+    /// columns are the columns in the table which reference the other table.
+    pub columns: Vec<String>,
+    /// referenced_table is the name of the table which is referenced.
+    pub referenced_table: String,
+    /// referenced_columns are the columns in the referenced table.
+    pub referenced_columns: Vec<String>,
+}
+
+/// IntrospectedIndex is the definition of an index read from a live database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntrospectedIndex {
+    /// name is the name of the index.
+    pub name: String,
+    /// columns are the columns of the index in order.
+    pub columns: Vec<String>,
+    /// unique is whether the index is a unique index.
+    pub unique: bool,
+}
+
+/// introspect_table will read the definition of a table from the database. None is returned if the table doesn't exist.
+/// Postgres is read from the system catalogs of the current schema, MySQL from information_schema of the current database and SQLite using the `table_info`, `foreign_key_list` and `index_list` pragmas.
+pub async fn introspect_table(
+    db: &DbConn,
+    table_name: &str,
+) -> Result<Option<IntrospectedTable>, DbErr> {
+    let (columns, foreign_keys, indexes) = match db.get_database_backend() {
+        DbBackend::Postgres => (
+            postgres_columns(db, table_name).await?,
+            postgres_foreign_keys(db, table_name).await?,
+            postgres_indexes(db, table_name).await?,
+        ),
+        DbBackend::MySql => (
+            mysql_columns(db, table_name).await?,
+            mysql_foreign_keys(db, table_name).await?,
+            mysql_indexes(db, table_name).await?,
+        ),
+        DbBackend::Sqlite => (
+            sqlite_columns(db, table_name).await?,
+            sqlite_foreign_keys(db, table_name).await?,
+            sqlite_indexes(db, table_name).await?,
+        ),
+    };
+
+    // Every table has at least one column so a table without any doesn't exist.
+    if columns.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(IntrospectedTable {
+        name: table_name.to_string(),
+        columns,
+        foreign_keys,
+        indexes,
+    }))
+}
+
+/// introspect_schema will read the definition of every table in the database except for the table sea_migrations uses to keep track of the applied migrations. The tables are ordered by name.
+///
+/// ```rust
+/// use sea_orm::{entity::prelude::*, Database};
+/// use sea_migrations::{introspect_schema, MigrationManager};
+///
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+/// #[sea_orm(table_name = "cake")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     pub name: String,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+/// pub enum Relation {}
+///
+/// impl ActiveModelBehavior for ActiveModel {}
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DbErr> {
+///     let db = Database::connect("sqlite::memory:").await?;
+///     MigrationManager::new(&db).create_table(Entity).await?;
+///
+///     let tables = introspect_schema(&db).await?;
+///     assert_eq!(tables.len(), 1);
+///     assert_eq!(tables[0].name, "cake");
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn introspect_schema(db: &DbConn) -> Result<Vec<IntrospectedTable>, DbErr> {
+    let backend = db.get_database_backend();
+    let sql = match backend {
+        DbBackend::Postgres => {
+            "SELECT tablename AS name FROM pg_tables WHERE schemaname = current_schema()"
+        }
+        DbBackend::MySql => "SELECT table_name AS name FROM information_schema.tables WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE'",
+        DbBackend::Sqlite => {
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'"
+        }
+    };
+
+    let mut names = db
+        .query_all(Statement::from_string(backend, sql.into()))
+        .await?
+        .iter()
+        .map(|row| row.try_get("", "name"))
+        .collect::<Result<Vec<String>, _>>()?;
+    names.retain(|name| name != MIGRATIONS_TABLE_NAME);
+    names.sort();
+
+    let mut tables = Vec::new();
+    for name in names {
+        if let Some(table) = introspect_table(db, &name).await? {
+            tables.push(table);
+        }
+    }
+
+    Ok(tables)
+}
+
+// postgres_columns reads the columns of a table on Postgres. `format_type` is used as it includes the length of the type and the name of enum types unlike information_schema.
+async fn postgres_columns(db: &DbConn, table_name: &str) -> Result<Vec<IntrospectedColumn>, DbErr> {
+    query(db, "SELECT a.attname AS name, format_type(a.atttypid, a.atttypmod) AS data_type, CASE WHEN a.attnotnull THEN 'NO' ELSE 'YES' END AS nullable FROM pg_attribute a JOIN pg_class c ON c.oid = a.attrelid JOIN pg_namespace n ON n.oid = c.relnamespace WHERE c.relname = $1 AND c.relkind IN ('r', 'p') AND n.nspname = current_schema() AND a.attnum > 0 AND NOT a.attisdropped ORDER BY a.attnum", table_name)
+        .await?
+        .iter()
+        .map(string_column)
+        .collect()
+}
+
+// postgres_foreign_keys reads the foreign keys of a table on Postgres.
+async fn postgres_foreign_keys(
+    db: &DbConn,
+    table_name: &str,
+) -> Result<Vec<IntrospectedForeignKey>, DbErr> {
+    query(db, "SELECT con.conname AS name, (SELECT string_agg(a.attname::text, ',' ORDER BY k.ord) FROM unnest(con.conkey) WITH ORDINALITY k(attnum, ord) JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum) AS columns, ref.relname AS referenced_table, (SELECT string_agg(a.attname::text, ',' ORDER BY k.ord) FROM unnest(con.confkey) WITH ORDINALITY k(attnum, ord) JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum) AS referenced_columns FROM pg_constraint con JOIN pg_class tbl ON tbl.oid = con.conrelid JOIN pg_class ref ON ref.oid = con.confrelid JOIN pg_namespace n ON n.oid = tbl.relnamespace WHERE con.contype = 'f' AND tbl.relname = $1 AND n.nspname = current_schema() ORDER BY con.conname", table_name)
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(IntrospectedForeignKey {
+                name: Some(row.try_get("", "name")?),
+                columns: split_columns(row.try_get("", "columns")?),
+                referenced_table: row.try_get("", "referenced_table")?,
+                referenced_columns: split_columns(row.try_get("", "referenced_columns")?),
+            })
+        })
+        .collect()
+}
+
+// postgres_indexes reads the indexes of a table on Postgres. Expression indexes only include the plain columns they use.
+async fn postgres_indexes(db: &DbConn, table_name: &str) -> Result<Vec<IntrospectedIndex>, DbErr> {
+    query(db, "SELECT i.relname AS name, CASE WHEN ix.indisunique THEN 'YES' ELSE 'NO' END AS is_unique, (SELECT string_agg(a.attname::text, ',' ORDER BY k.ord) FROM unnest(ix.indkey::int2[]) WITH ORDINALITY k(attnum, ord) JOIN pg_attribute a ON a.attrelid = ix.indrelid AND a.attnum = k.attnum) AS columns FROM pg_index ix JOIN pg_class t ON t.oid = ix.indrelid JOIN pg_class i ON i.oid = ix.indexrelid JOIN pg_namespace n ON n.oid = t.relnamespace WHERE t.relname = $1 AND n.nspname = current_schema() AND NOT ix.indisprimary ORDER BY i.relname", table_name)
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(IntrospectedIndex {
+                name: row.try_get("", "name")?,
+                columns: split_columns(row.try_get("", "columns")?),
+                unique: row.try_get::<String>("", "is_unique")? == "YES",
+            })
+        })
+        .collect()
+}
+
+// mysql_columns reads the columns of a table on MySQL.
+async fn mysql_columns(db: &DbConn, table_name: &str) -> Result<Vec<IntrospectedColumn>, DbErr> {
+    query(db, "SELECT column_name AS name, column_type AS data_type, is_nullable AS nullable FROM information_schema.columns WHERE table_schema = DATABASE() AND table_name = ? ORDER BY ordinal_position", table_name)
+        .await?
+        .iter()
+        .map(string_column)
+        .collect()
+}
+
+// mysql_foreign_keys reads the foreign keys of a table on MySQL. information_schema has a row for each column so they are grouped by the name of the constraint.
+async fn mysql_foreign_keys(
+    db: &DbConn,
+    table_name: &str,
+) -> Result<Vec<IntrospectedForeignKey>, DbErr> {
+    let mut foreign_keys = BTreeMap::<String, IntrospectedForeignKey>::new();
+    for row in query(db, "SELECT constraint_name AS name, column_name AS column_name, referenced_table_name AS referenced_table, referenced_column_name AS referenced_column FROM information_schema.key_column_usage WHERE table_schema = DATABASE() AND table_name = ? AND referenced_table_name IS NOT NULL ORDER BY constraint_name, ordinal_position", table_name).await? {
+        let foreign_key = match foreign_keys.entry(row.try_get("", "name")?) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let name = entry.key().clone();
+                entry.insert(IntrospectedForeignKey {
+                    name: Some(name),
+                    columns: Vec::new(),
+                    referenced_table: row.try_get("", "referenced_table")?,
+                    referenced_columns: Vec::new(),
+                })
+            }
+        };
+        foreign_key.columns.push(row.try_get("", "column_name")?);
+        foreign_key
+            .referenced_columns
+            .push(row.try_get("", "referenced_column")?);
+    }
+
+    Ok(foreign_keys.into_values().collect())
+}
+
+// mysql_indexes reads the indexes of a table on MySQL. information_schema has a row for each column so they are grouped by the name of the index.
+async fn mysql_indexes(db: &DbConn, table_name: &str) -> Result<Vec<IntrospectedIndex>, DbErr> {
+    let mut indexes = BTreeMap::<String, IntrospectedIndex>::new();
+    for row in query(db, "SELECT index_name AS name, column_name AS column_name, IF(non_unique = 0, 'YES', 'NO') AS is_unique FROM information_schema.statistics WHERE table_schema = DATABASE() AND table_name = ? AND index_name != 'PRIMARY' ORDER BY index_name, seq_in_index", table_name).await? {
+        let index = match indexes.entry(row.try_get("", "name")?) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let name = entry.key().clone();
+                entry.insert(IntrospectedIndex {
+                    name,
+                    columns: Vec::new(),
+                    unique: row.try_get::<String>("", "is_unique")? == "YES",
+                })
+            }
+        };
+        // The column is NULL for functional indexes.
+        if let Some(column) = row.try_get::<Option<String>>("", "column_name")? {
+            index.columns.push(column);
+        }
+    }
+
+    Ok(indexes.into_values().collect())
+}
+
+// sqlite_columns reads the columns of a table on SQLite. The data type is the type the column was declared with.
+async fn sqlite_columns(db: &DbConn, table_name: &str) -> Result<Vec<IntrospectedColumn>, DbErr> {
+    query(
+        db,
+        "SELECT name, type, \"notnull\" FROM pragma_table_info(?) ORDER BY cid",
+        table_name,
+    )
+    .await?
+    .iter()
+    .map(|row| {
+        Ok(IntrospectedColumn {
+            name: row.try_get("", "name")?,
+            data_type: row.try_get("", "type")?,
+            nullable: row.try_get::<i32>("", "notnull")? == 0,
+        })
+    })
+    .collect()
+}
+
+// sqlite_foreign_keys reads the foreign keys of a table on SQLite. The pragma has a row for each column so they are grouped by the id of the foreign key.
+async fn sqlite_foreign_keys(
+    db: &DbConn,
+    table_name: &str,
+) -> Result<Vec<IntrospectedForeignKey>, DbErr> {
+    let mut foreign_keys = BTreeMap::<i32, IntrospectedForeignKey>::new();
+    for row in query(
+        db,
+        "SELECT id, \"table\", \"from\", \"to\" FROM pragma_foreign_key_list(?) ORDER BY id, seq",
+        table_name,
+    )
+    .await?
+    {
+        let foreign_key = match foreign_keys.entry(row.try_get("", "id")?) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(IntrospectedForeignKey {
+                name: None,
+                columns: Vec::new(),
+                referenced_table: row.try_get("", "table")?,
+                referenced_columns: Vec::new(),
+            }),
+        };
+        foreign_key.columns.push(row.try_get("", "from")?);
+        // `to` is NULL when the foreign key references the primary key of the other table without naming the columns.
+        if let Some(column) = row.try_get::<Option<String>>("", "to")? {
+            foreign_key.referenced_columns.push(column);
+        }
+    }
+
+    Ok(foreign_keys.into_values().collect())
+}
+
+// sqlite_indexes reads the indexes of a table on SQLite. The index SQLite creates for the primary key is skipped.
+async fn sqlite_indexes(db: &DbConn, table_name: &str) -> Result<Vec<IntrospectedIndex>, DbErr> {
+    let mut indexes = Vec::new();
+    for row in query(
+        db,
+        "SELECT name, \"unique\" FROM pragma_index_list(?) WHERE origin != 'pk' ORDER BY name",
+        table_name,
+    )
+    .await?
+    {
+        let name: String = row.try_get("", "name")?;
+        let columns = query(
+            db,
+            "SELECT name FROM pragma_index_info(?) WHERE name IS NOT NULL ORDER BY seqno",
+            &name,
+        )
+        .await?
+        .iter()
+        .map(|row| row.try_get("", "name"))
+        .collect::<Result<_, _>>()?;
+        indexes.push(IntrospectedIndex {
+            name,
+            columns,
+            unique: row.try_get::<i32>("", "unique")? == 1,
+        });
+    }
+
+    Ok(indexes)
+}
+
+// query runs a query with the name of a table (or index) as it's only parameter.
+async fn query(db: &DbConn, sql: &str, name: &str) -> Result<Vec<QueryResult>, DbErr> {
+    db.query_all(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        sql,
+        vec![name.into()],
+    ))
+    .await
+}
+
+// string_column converts a row with `name`, `data_type` and a `YES` or `NO` `nullable` column into an IntrospectedColumn.
+fn string_column(row: &QueryResult) -> Result<IntrospectedColumn, DbErr> {
+    Ok(IntrospectedColumn {
+        name: row.try_get("", "name")?,
+        data_type: row.try_get("", "data_type")?,
+        nullable: row.try_get::<String>("", "nullable")? == "YES",
+    })
+}
+
+// split_columns splits a comma separated list of column names returned by `string_agg`.
+fn split_columns(columns: Option<String>) -> Vec<String> {
+    columns
+        .map(|columns| columns.split(',').map(String::from).collect())
+        .unwrap_or_default()
+}
@@ -6,16 +6,50 @@
 //! Checkout an example using this package [here](https://github.com/oscartbeaumont/sea-migrations/tree/main/example).
 
 use async_trait::async_trait;
+use std::{sync::Arc, time::Duration};
+
 use sea_orm::{
-    sea_query::Table, ColumnTrait, ConnectionTrait, DbConn, DbErr, EntityTrait, ExecResult,
-    Iterable, RelationTrait,
+    sea_query::{
+        extension::postgres::Type, Alias, ForeignKey, Iden, Index, PostgresQueryBuilder,
+        SelectStatement, SimpleExpr, Table,
+    },
+    ActiveEnum, ColumnTrait, ColumnType, ConnectionTrait, DbBackend, DbConn, DbErr, EntityTrait,
+    ExecResult, PrimaryKeyToColumn, RelationTrait, Statement,
 };
 
-use crate::seaorm_integration::*;
+use crate::{
+    catch_unwind::catch_unwind, migrations_table::LockGuard, seaorm_integration::*,
+    table_schema::schema_column,
+};
+pub use backend_sql::*;
+pub use column_default::*;
+pub use error::*;
+pub use introspection::*;
+pub use naming::*;
+pub use schema_diff::*;
 pub use sea_migrations_derive::*;
+pub use sql_migration::*;
+pub use table_schema::*;
+pub use trigger::*;
 
+mod autogenerate;
+mod backend_sql;
+mod catch_unwind;
+mod column_default;
+mod drop_all;
+mod error;
+mod introspection;
 mod migrations_table;
+mod naming;
+mod schema_diff;
 mod seaorm_integration;
+mod sql_migration;
+mod sqlite;
+mod table_schema;
+/// testing contains helpers for checking your migrations in tests. It requires the `testing` feature.
+#[cfg(feature = "testing")]
+pub mod testing;
+mod trigger;
 
 /// MigrationName is the trait implemented on a migration so that sea_migration knows what the migration is called. This is automatically derived by the 'Migration' derive macro.
 /// ```rust
@@ -53,7 +87,7 @@ pub trait MigrationName {
 /// ```
 #[async_trait]
 pub trait MigratorTrait: MigrationName {
-    /// up is run to apply a database migration. You can assume anything created in here doesn't exist when it is run. If an error occurs the `down` method will be run to undo the migration before retrying.
+    /// up is run to apply a database migration. You can assume anything created in here doesn't exist when it is run. If an error occurs the `down` method will be run to undo the migration before retrying (unless disabled using [RollbackPolicy::Skip]).
     async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr>;
 
     /// down is used to undo a database migration. You should assume that anything applied in the `up` function is not necessarily created when this is run as the `up` function may have failed.
@@ -61,18 +95,121 @@ pub trait MigratorTrait: MigrationName {
 }
 
 /// MigrationManager is used to manage migrations. It holds the database connection and has many helpers to make your database migration code concise.
+/// It also holds the settings used to create the schema so it must be created using `MigrationManager::new` or `MigrationManager::with_options` instead of the `MigrationManager { db }` struct literal supported by earlier versions.
 pub struct MigrationManager<'a> {
     /// db holds the database connection. This can be used to run any custom queries again the database.
     pub db: &'a DbConn,
+    naming_convention: Arc<dyn NamingConvention>,
+    postgres_auto_increment: PostgresAutoIncrement,
 }
 
 impl<'a> MigrationManager<'a> {
     /// new will create a new MigrationManager. This is primarily designed for internal use but is exposed in case you want to use it.
     pub fn new(db: &'a DbConn) -> Self {
-        Self { db }
+        Self::with_options(db, &MigratorOptions::default())
+    }
+
+    /// with_naming_convention will create a new MigrationManager which names the constraints and indexes it creates using the provided NamingConvention.
+    pub fn with_naming_convention(
+        db: &'a DbConn,
+        naming_convention: Arc<dyn NamingConvention>,
+    ) -> Self {
+        Self::with_options(
+            db,
+            &MigratorOptions {
+                naming_convention,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// with_options will create a new MigrationManager which uses the schema settings (such as the naming convention) from the provided MigratorOptions.
+    pub fn with_options(db: &'a DbConn, options: &MigratorOptions) -> Self {
+        Self {
+            db,
+            naming_convention: options.naming_convention.clone(),
+            postgres_auto_increment: options.postgres_auto_increment,
+        }
+    }
+
+    /// naming_convention returns the NamingConvention used to name the constraints and indexes created by this MigrationManager. This can be used to compute the name of a constraint or index so it can be referenced in a custom query.
+    pub fn naming_convention(&self) -> &dyn NamingConvention {
+        self.naming_convention.as_ref()
+    }
+
+    // schema_context returns the settings used to convert SeaORM Entities into statements for the connected database.
+    fn schema_context(&self) -> SchemaContext<'_> {
+        SchemaContext {
+            backend: self.db.get_database_backend(),
+            naming: self.naming_convention(),
+            postgres_auto_increment: self.postgres_auto_increment,
+        }
+    }
+
+    /// foreign_key_name returns the name of the foreign key constraint created for a Relation on a SeaORM Entity.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod tax_info {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "tax_info")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// mod customer {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "customer")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub tax_info_id: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {
+    ///         #[sea_orm(
+    ///             belongs_to = "super::tax_info::Entity",
+    ///             from = "Column::TaxInfoId",
+    ///             to = "super::tax_info::Column::Id"
+    ///         )]
+    ///         TaxInfo,
+    ///     }
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     let mg = MigrationManager::new(&db);
+    ///
+    ///     assert_eq!(mg.foreign_key_name(customer::Relation::TaxInfo), "fk_customer_tax_info_id_tax_info");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn foreign_key_name<R: RelationTrait>(&self, relation: R) -> String {
+        get_foreign_key_name(&relation.def(), self.naming_convention())
     }
 
     /// create_table will create a database table if it does not exist for a SeaORM Entity.
+    /// Entities with a composite primary key (such as the join table of a many-to-many relationship) are created with a table level primary key.
+    /// Integer primary keys of any size are auto incremented unless `auto_increment = false` is set on the Model. On Postgres they can be created as identity columns instead of serial columns using [MigratorOptions::postgres_auto_increment].
+    /// Columns using a SeaORM ActiveEnum with `db_type = "Enum"` use the enum type on Postgres (which is created if it does not exist). Other databases store them as strings with a check constraint restricting them to the values of the enum.
     ///
     /// ```rust
     /// use sea_orm::{Database, DbErr};
@@ -107,23 +244,40 @@ impl<'a> MigrationManager<'a> {
     where
         E: EntityTrait,
     {
-        let mut stmt = Table::create();
-        stmt.table(entity).if_not_exists();
+        self.create_table_from_schema(&TableSchema::from_entity(entity))
+            .await
+    }
 
-        for column in E::Column::iter() {
-            stmt.col(&mut get_column_def::<E>(column));
+    /// create_table_from_schema will create a database table if it does not exist from a TableSchema. The table is created the same way `create_table` creates the table of the Entity the TableSchema was captured from.
+    /// Refer to [TableSchema] for an example.
+    pub async fn create_table_from_schema(&self, table: &TableSchema) -> Result<ExecResult, DbErr> {
+        let backend = self.db.get_database_backend();
+        let ctx = self.schema_context();
+
+        for (name, values) in get_schema_enum_types(table, |_| true) {
+            self.create_enum_type(&name, &values).await?;
         }
 
-        for relation in E::Relation::iter() {
-            if relation.def().is_owner {
-                continue;
+        let index_stmts = get_schema_index_defs(table, &ctx, |_| true);
+        let mut stmt = get_schema_create_def(table, &ctx);
+        stmt.if_not_exists();
+        // MySQL doesn't support `CREATE INDEX IF NOT EXISTS` so the indexes are created as part of the table instead.
+        if backend == DbBackend::MySql {
+            for mut index in index_stmts.clone() {
+                stmt.index(&mut index);
             }
-            stmt.foreign_key(&mut get_column_foreign_key_def::<E>(relation));
         }
+        let result = self.db.execute(backend.build(&stmt)).await?;
 
-        self.db
-            .execute(self.db.get_database_backend().build(&stmt))
-            .await
+        if backend != DbBackend::MySql {
+            for stmt in index_stmts {
+                self.db
+                    .execute(build_index_if_not_exists_stmt(backend, &stmt))
+                    .await?;
+            }
+        }
+
+        Ok(result)
     }
 
     /// drop_table will drop a database table and all of it's data for a SeaORM Entity.
@@ -167,6 +321,118 @@ impl<'a> MigrationManager<'a> {
             .await
     }
 
+    /// drop_table_from_schema will drop the database table of a TableSchema and all of it's data. This is used to undo `create_table_from_schema`.
+    pub async fn drop_table_from_schema(&self, table: &TableSchema) -> Result<ExecResult, DbErr> {
+        let stmt = Table::drop()
+            .table(Alias::new(&table.name))
+            .if_exists()
+            .to_owned();
+        self.db
+            .execute(self.db.get_database_backend().build(&stmt))
+            .await
+    }
+
+    /// create_enum will create the database enum type for a SeaORM ActiveEnum if it does not exist. The type is named after the enum (set using `enum_name`) and has a value for each variant.
+    /// Only Postgres has enum types so this does nothing on other databases. Columns using the enum are instead stored as strings with a check constraint restricting them to the values of the enum.
+    /// `create_table` and `add_column` automatically create the enum types used by the Entity so you only need this to create a type ahead of time.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod tea {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Debug, Clone, PartialEq, EnumIter, DeriveActiveEnum)]
+    ///     #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "tea")]
+    ///     pub enum Tea {
+    ///         #[sea_orm(string_value = "EverydayTea")]
+    ///         EverydayTea,
+    ///         #[sea_orm(string_value = "BreakfastTea")]
+    ///         BreakfastTea,
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///
+    ///     mg.create_enum::<tea::Tea>().await?; // Replace "tea::Tea" with your SeaORM ActiveEnum.
+    ///     mg.add_enum_value(tea::Tea::BreakfastTea).await?;
+    ///     mg.drop_enum::<tea::Tea>().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_enum<A>(&self) -> Result<(), DbErr>
+    where
+        A: ActiveEnum<Value = String>,
+    {
+        self.create_enum_type(&A::name(), &A::values()).await
+    }
+
+    /// drop_enum will drop the database enum type for a SeaORM ActiveEnum if it exists. The tables using the enum must be dropped first. This does nothing on databases other than Postgres.
+    /// Refer to `create_enum` for an example.
+    pub async fn drop_enum<A>(&self) -> Result<(), DbErr>
+    where
+        A: ActiveEnum<Value = String>,
+    {
+        if self.db.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        let stmt = Type::drop()
+            .if_exists()
+            .name(Alias::new(&A::name()))
+            .to_owned();
+        self.execute_sql(stmt.to_string(PostgresQueryBuilder))
+            .await?;
+        Ok(())
+    }
+
+    /// add_enum_value will add a new variant of a SeaORM ActiveEnum to the existing database enum type on Postgres. Postgres can't remove values from an enum type so this can't be undone in the `down` function.
+    /// On other databases enum columns are stored as strings so this does nothing. Use `alter_column` to update the check constraint of each column using the enum instead.
+    /// Refer to `create_enum` for an example.
+    pub async fn add_enum_value<A>(&self, variant: A) -> Result<(), DbErr>
+    where
+        A: ActiveEnum<Value = String>,
+    {
+        let backend = self.db.get_database_backend();
+        if backend != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        self.execute_sql(format!(
+            "ALTER TYPE {} ADD VALUE IF NOT EXISTS {}",
+            quote_iden(backend, &Alias::new(&A::name())),
+            quote_string(&variant.into_value())
+        ))
+        .await?;
+        Ok(())
+    }
+
+    // create_enum_type creates an enum type on Postgres if it does not exist. It does nothing on other databases.
+    async fn create_enum_type(&self, name: &str, values: &[String]) -> Result<(), DbErr> {
+        let backend = self.db.get_database_backend();
+        if backend != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        // Postgres doesn't support `CREATE TYPE IF NOT EXISTS` so the error raised when the type already exists is ignored instead. Checking whether the type exists first would race with other connections creating it.
+        let stmt = Type::create()
+            .as_enum(Alias::new(name))
+            .values(values.iter().map(|value| Alias::new(value)))
+            .to_owned();
+        self.execute_sql(format!(
+            "DO $$ BEGIN {}; EXCEPTION WHEN duplicate_object THEN NULL; END $$",
+            stmt.to_string(PostgresQueryBuilder)
+        ))
+        .await?;
+        Ok(())
+    }
+
     /// add_column will automatically create a new column in the existing database table for a specific column on the Entity.
     ///
     /// ```rust
@@ -229,19 +495,236 @@ impl<'a> MigrationManager<'a> {
         E: EntityTrait<Column = T>,
         T: ColumnTrait,
     {
+        self.add_column_def(&TableSchema::from_entity(entity), column.as_str(), None)
+            .await
+    }
+
+    /// add_column_with_default will create a new column in the existing database table for a specific column on the Entity with a default value. The default value is used to fill in the column for the existing rows in the table which is required to add a non-null column to a table containing data.
+    /// SQLite can't add a column with a default expression (such as `ColumnDefault::current_timestamp()`) to a table containing data so on SQLite the table is rebuilt from the Entity instead. The Entity must describe every column of the table for this to work.
+    ///
+    /// ```rust
+    /// use sea_orm::{ActiveModelTrait, Database, DbErr, EntityTrait, Set};
+    /// use sea_migrations::{ColumnDefault, MigrationManager};
+    ///
+    /// mod original_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// mod updated_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///         pub slices: i32,
+    ///         pub created_at: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(original_model::Entity).await?; // Create the original table without the new column. This would have been done in the previous version of your application.
+    ///     original_model::ActiveModel {
+    ///         name: Set("Sponge".to_string()),
+    ///         ..Default::default()
+    ///     }
+    ///     .insert(&db)
+    ///     .await?;
+    ///
+    ///     mg.add_column_with_default(updated_model::Entity, updated_model::Column::Slices, ColumnDefault::value(8)).await?; // Replace "updated_model" with the name of the module containing your SeaORM Model and Slices with the name of your new Column.
+    ///
+    ///     mg.add_column_with_default(updated_model::Entity, updated_model::Column::CreatedAt, ColumnDefault::current_timestamp()).await?;
+    ///
+    ///     // The existing row was filled in with the default values.
+    ///     let cake = updated_model::Entity::find_by_id(1).one(&db).await?.unwrap();
+    ///     assert_eq!(cake.slices, 8);
+    ///     assert!(!cake.created_at.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn add_column_with_default<E: 'static, T: 'static>(
+        &self,
+        entity: E,
+        column: T,
+        default: ColumnDefault,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        T: ColumnTrait,
+    {
+        self.add_column_def(
+            &TableSchema::from_entity(entity),
+            column.as_str(),
+            Some(default),
+        )
+        .await
+    }
+
+    /// add_column_from_schema will create a new column in the existing database table for a column of a TableSchema. This works like `add_column` but the column doesn't need to exist on the current version of the Entity.
+    ///
+    /// ```rust
+    /// use sea_orm::{ColumnType, Database, DbErr};
+    /// use sea_migrations::{ColumnSchema, IndexSchema, MigrationManager, TableSchema};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///
+    ///     // This would normally be rendered by `TableSchema::to_rust_source` when the migration was written.
+    ///     let column = |name: &str, col_type, auto_increment| ColumnSchema { name: name.into(), col_type, nullable: false, default: None, auto_increment };
+    ///     let mut table = TableSchema {
+    ///         name: "cake".into(),
+    ///         columns: vec![column("id", ColumnType::Integer, true), column("name", ColumnType::String(None), false)],
+    ///         primary_key: vec!["id".into()],
+    ///         foreign_keys: vec![],
+    ///         indexes: vec![IndexSchema { columns: vec!["name".into()], unique: true }],
+    ///     };
+    ///     mg.create_table_from_schema(&table).await?; // Create the original table without the new column. This would have been done in the previous version of your application.
+    ///
+    ///     table.columns.push(ColumnSchema { nullable: true, ..column("description", ColumnType::Text, false) });
+    ///     mg.add_column_from_schema(&table, "description").await?;
+    ///     mg.drop_column_from_schema(&table, "description").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn add_column_from_schema(
+        &self,
+        table: &TableSchema,
+        column: &str,
+    ) -> Result<ExecResult, DbErr> {
+        self.add_column_def(table, column, None).await
+    }
+
+    /// add_column_from_schema_with_default will create a new column in the existing database table for a column of a TableSchema. Existing rows are set to the provided default value. Refer to `add_column_with_default` for more information.
+    pub async fn add_column_from_schema_with_default(
+        &self,
+        table: &TableSchema,
+        column: &str,
+        default: ColumnDefault,
+    ) -> Result<ExecResult, DbErr> {
+        self.add_column_def(table, column, Some(default)).await
+    }
+
+    // add_column_def adds a column of a TableSchema to the existing table with an optional default value. It is used to implement `add_column` and `add_column_from_schema` (and their `_with_default` variants).
+    async fn add_column_def(
+        &self,
+        table: &TableSchema,
+        column: &str,
+        default: Option<ColumnDefault>,
+    ) -> Result<ExecResult, DbErr> {
+        let backend = self.db.get_database_backend();
+        let ctx = self.schema_context();
+        schema_column(table, column)?;
+        let mut table = table.clone();
+        if let Some(default) = default {
+            for c in table.columns.iter_mut().filter(|c| c.name == column) {
+                c.default = Some(default.clone());
+            }
+        }
+        let column_schema = schema_column(&table, column)?;
+        for (name, values) in get_schema_enum_types(&table, |c| c.name == column) {
+            self.create_enum_type(&name, &values).await?;
+        }
+
+        // SQLite can't add a column with a default which isn't a constant (such as `CURRENT_TIMESTAMP`) to a table containing rows so the table is rebuilt with the column instead.
+        if backend == DbBackend::Sqlite
+            && matches!(column_schema.default, Some(ColumnDefault::Expr(_)))
+        {
+            return self
+                .rebuild_sqlite_table(&table, |_| true, |_| true, None, None)
+                .await;
+        }
+
         let mut stmt = Table::alter();
-        stmt.table(entity)
-            .add_column(&mut get_column_def::<E>(column));
+        stmt.table(Alias::new(&table.name))
+            .add_column(&mut get_schema_column_def(&table, column_schema, &ctx));
+        let result = self.db.execute(backend.build(&stmt)).await?;
+
+        for stmt in get_schema_index_defs(&table, &ctx, |index| {
+            index.columns.iter().any(|c| c == column)
+        }) {
+            self.db.execute(build_schema_stmt(backend, &stmt)).await?;
+        }
+
+        Ok(result)
+    }
 
+    // execute_sql runs a raw SQL statement against the database.
+    async fn execute_sql(&self, sql: String) -> Result<ExecResult, DbErr> {
         self.db
-            .execute(self.db.get_database_backend().build(&stmt))
+            .execute(Statement::from_string(self.db.get_database_backend(), sql))
             .await
     }
 
+    // drop_index_if_exists drops an index on the table of a SeaORM Entity if it exists.
+    async fn drop_index_if_exists<E: 'static>(&self, entity: E, name: &str) -> Result<(), DbErr>
+    where
+        E: EntityTrait,
+    {
+        let backend = self.db.get_database_backend();
+        let index_name = quote_iden(backend, &Alias::new(name));
+        match backend {
+            DbBackend::Postgres | DbBackend::Sqlite => {
+                self.execute_sql(format!("DROP INDEX IF EXISTS {}", index_name))
+                    .await?;
+            }
+            DbBackend::MySql => {
+                let index_exists = self
+                    .db
+                    .query_one(Statement::from_sql_and_values(
+                        backend,
+                        "SELECT 1 FROM information_schema.statistics WHERE table_schema = DATABASE() AND table_name = ? AND index_name = ?",
+                        vec![entity.table_name().into(), name.into()],
+                    ))
+                    .await?
+                    .is_some();
+
+                if index_exists {
+                    self.execute_sql(format!(
+                        "DROP INDEX {} ON {}",
+                        index_name,
+                        quote_iden(backend, &entity)
+                    ))
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// drop_column will drop a table's column and all of it's data for a Column on a SeaORM Entity.
+    /// SQLite can't drop columns so on SQLite the table is rebuilt from the Entity without the column instead. The Entity must describe every column of the table for this to work. Use `drop_column_from_schema` with a [TableSchema] captured from the Entity if the column will be removed from the Entity later on.
     ///
-    /// The example panics due to SQLite not being able to drop a column.
-    /// ```should_panic
+    /// ```rust
     /// use sea_orm::{Database, DbErr};
     /// use sea_orm::entity::prelude::*;
     /// use sea_migrations::MigrationManager;
@@ -252,7 +735,7 @@ impl<'a> MigrationManager<'a> {
     ///     #[sea_orm(primary_key)]
     ///     pub id: i32,
     ///     pub name: String,
-    ///     pub column_to_remove: String, // Note: This column although removed from the database can NEVER be removed from the Model without causing issues with running older migrations.
+    ///     pub column_to_remove: String, // Note: This column although removed from the database can't be removed from the Model without breaking this migration. Use `drop_column_from_schema` if it will be removed.
     /// }
     ///
     /// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -281,82 +764,1918 @@ impl<'a> MigrationManager<'a> {
         E: EntityTrait<Column = T>,
         T: ColumnTrait,
     {
+        self.drop_column_from_schema(&TableSchema::from_entity(entity), column.as_str())
+            .await
+    }
+
+    /// drop_column_from_schema will drop a table's column and all of it's data for a column of a TableSchema. On SQLite the table is rebuilt from the TableSchema without the column so the TableSchema must describe every column of the table.
+    /// Refer to `add_column_from_schema` for an example.
+    pub async fn drop_column_from_schema(
+        &self,
+        table: &TableSchema,
+        column: &str,
+    ) -> Result<ExecResult, DbErr> {
+        schema_column(table, column)?;
+        if self.db.get_database_backend() == DbBackend::Sqlite {
+            return self
+                .rebuild_sqlite_table(table, |c| c.name != column, |_| true, None, None)
+                .await;
+        }
+
         let mut stmt = Table::alter();
-        stmt.table(entity).drop_column(column);
+        stmt.table(Alias::new(&table.name))
+            .drop_column(Alias::new(column));
 
         self.db
             .execute(self.db.get_database_backend().build(&stmt))
             .await
     }
-}
 
-/// Migrator is used to handle running migration operations.
-pub struct Migrator;
-
-impl Migrator {
-    /// run will run all of the database migrations provided via the migrations parameter.
-    /// In microservice environments think about how this function is called. It contains an internal lock to prevent multiple clients running migrations at the same time but don't rely on it!
+    /// add_foreign_key will add the foreign key constraint for a Relation on a SeaORM Entity to the existing database table. This is useful when the data in the table must be backfilled before the constraint can be added.
+    /// The constraint is named `fk_<table>_<column>_<referenced table>` so it can later be dropped using `drop_foreign_key`. SQLite can't add foreign keys to an existing table so on SQLite the table is rebuilt from the Entity instead. The rebuilt table keeps the foreign keys which already exist on the table and only adds the one for the Relation.
     ///
     /// ```rust
-    /// use sea_migrations::Migrator;
-    /// use sea_orm::{ Database, DbErr };
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_migrations::{introspect_table, MigrationManager};
     ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), DbErr> {
-    ///     let db = Database::connect("sqlite::memory:").await?;
-    ///     
-    ///     Migrator::run(
-    ///         &db,
-    ///         &mut vec![
-    ///            // Box::new(models::M20210101020202DoAThing),
-    ///         ],
-    ///     )
-    ///     .await
+    /// mod tax_info {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "tax_info")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
     /// }
     ///
-    /// ```
-    // Note(oscar): I don't like that the migrations argument is mutable but it works for now and that argument will be removed in a future version so their is no point trying to fix it.
+    /// mod store {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "store")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// mod original_customer {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "customer")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub tax_info_id: i32,
+    ///         pub store_id: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// mod updated_customer {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "customer")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub tax_info_id: i32,
+    ///         pub store_id: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {
+    ///         #[sea_orm(
+    ///             belongs_to = "super::tax_info::Entity",
+    ///             from = "Column::TaxInfoId",
+    ///             to = "super::tax_info::Column::Id"
+    ///         )]
+    ///         TaxInfo,
+    ///         #[sea_orm(
+    ///             belongs_to = "super::store::Entity",
+    ///             from = "Column::StoreId",
+    ///             to = "super::store::Column::Id"
+    ///         )]
+    ///         Store,
+    ///     }
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(tax_info::Entity).await?;
+    ///     mg.create_table(store::Entity).await?;
+    ///     mg.create_table(original_customer::Entity).await?; // Create the original table without the foreign keys. This would have been done in the previous version of your application.
+    ///
+    ///     mg.add_foreign_key(updated_customer::Entity, updated_customer::Relation::TaxInfo).await?; // Replace "updated_customer" with the name of the module containing your SeaORM Model and TaxInfo with the name of the Relation.
+    ///
+    ///     // Only the foreign key for the TaxInfo relation was added. The Store relation is left for a later migration.
+    ///     let customer = introspect_table(&db, "customer").await?.unwrap();
+    ///     assert_eq!(customer.foreign_keys.len(), 1);
+    ///     assert_eq!(customer.foreign_keys[0].referenced_table, "tax_info");
+    ///
+    ///     mg.drop_foreign_key(updated_customer::Entity, updated_customer::Relation::TaxInfo).await?;
+    ///     assert!(introspect_table(&db, "customer").await?.unwrap().foreign_keys.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn add_foreign_key<E: 'static, R: 'static>(
+        &self,
+        entity: E,
+        relation: R,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait<Relation = R>,
+        R: RelationTrait,
+    {
+        let backend = self.db.get_database_backend();
+        let foreign_key = get_foreign_key_schema(&relation.def());
+        if backend == DbBackend::Sqlite {
+            let table = TableSchema::from_entity(entity);
+            let existing = self.existing_foreign_keys(&table.name).await?;
+            return self
+                .rebuild_sqlite_table(
+                    &table,
+                    |_| true,
+                    |fk| {
+                        (fk.columns == foreign_key.columns
+                            && fk.referenced_table == foreign_key.referenced_table)
+                            || existing(fk)
+                    },
+                    None,
+                    None,
+                )
+                .await;
+        }
+
+        self.db
+            .execute(build_schema_stmt(
+                backend,
+                &get_schema_foreign_key_def(
+                    entity.table_name(),
+                    &foreign_key,
+                    self.naming_convention(),
+                ),
+            ))
+            .await
+    }
+
+    /// drop_foreign_key will drop the foreign key constraint created for a Relation on a SeaORM Entity from the database table.
+    /// SQLite can't drop foreign keys from an existing table so on SQLite the table is rebuilt from the Entity without the foreign key instead.
+    /// Refer to `add_foreign_key` for an example.
+    pub async fn drop_foreign_key<E: 'static, R: 'static>(
+        &self,
+        entity: E,
+        relation: R,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait<Relation = R>,
+        R: RelationTrait,
+    {
+        self.drop_foreign_key_by_name(entity, &self.foreign_key_name(relation))
+            .await
+    }
+
+    /// drop_foreign_key_by_name will drop a foreign key constraint from the database table of a SeaORM Entity using the name of the constraint.
+    /// SQLite can't drop foreign keys from an existing table so on SQLite the table is rebuilt from the Entity with the foreign keys which already exist on the table except for this one. On SQLite the name must be the name sea_migrations gives to the foreign key of a Relation on the Entity.
+    pub async fn drop_foreign_key_by_name<E: 'static>(
+        &self,
+        entity: E,
+        name: &str,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait,
+    {
+        let backend = self.db.get_database_backend();
+        if backend == DbBackend::Sqlite {
+            let table = TableSchema::from_entity(entity);
+            let existing = self.existing_foreign_keys(&table.name).await?;
+            return self
+                .rebuild_sqlite_table(
+                    &table,
+                    |_| true,
+                    |foreign_key| {
+                        existing(foreign_key)
+                            && get_schema_foreign_key_name(
+                                &table.name,
+                                foreign_key,
+                                self.naming_convention(),
+                            ) != name
+                    },
+                    None,
+                    None,
+                )
+                .await;
+        }
+
+        let stmt = ForeignKey::drop().name(name).table(entity).to_owned();
+        self.db.execute(build_schema_stmt(backend, &stmt)).await
+    }
+
+    /// add_check_constraint will add a check constraint with the given name to the existing database table of a SeaORM Entity. The constraint is built from a sea_query expression (eg. `Expr::col(Column::Price).gte(0)`).
+    /// SQLite can't add constraints to an existing table so on SQLite the table is rebuilt with the constraint instead. The constraint is kept when the table is rebuilt by other helpers so it must be dropped before dropping a column it refers to.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_orm::sea_query::Expr;
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod cake {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///         pub bakery: String,
+    ///         pub price: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(cake::Entity).await?;
+    ///
+    ///     mg.add_check_constraint(cake::Entity, "ck_cake_price", Expr::col(cake::Column::Price).gte(0)).await?;
+    ///     mg.add_unique_constraint(cake::Entity, [cake::Column::Name, cake::Column::Bakery]).await?; // Named "uq_cake_name_bakery" by the default NamingConvention.
+    ///
+    ///     mg.drop_constraint(cake::Entity, "ck_cake_price").await?;
+    ///     mg.drop_constraint(cake::Entity, &mg.naming_convention().unique("cake", &["name", "bakery"])).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn add_check_constraint<E: 'static>(
+        &self,
+        entity: E,
+        name: &str,
+        expr: SimpleExpr,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait,
+    {
+        let backend = self.db.get_database_backend();
+        let constraint = format!(
+            "CONSTRAINT {} CHECK ({})",
+            quote_iden(backend, &Alias::new(name)),
+            build_simple_expr(backend, &expr)
+        );
+
+        if backend == DbBackend::Sqlite {
+            return sqlite::alter_table_definitions(self.db, entity.table_name(), |definitions| {
+                definitions.push(constraint);
+                Ok(())
+            })
+            .await;
+        }
+
+        self.execute_sql(format!(
+            "ALTER TABLE {} ADD {}",
+            quote_iden(backend, &entity),
+            constraint
+        ))
+        .await
+    }
+
+    /// add_unique_constraint will add a unique constraint over multiple Columns of a SeaORM Entity to the existing database table. The constraint is named using [NamingConvention::unique].
+    /// On SQLite a unique index is created instead as constraints can't be added to an existing table.
+    /// Refer to `add_check_constraint` for an example.
+    pub async fn add_unique_constraint<E: 'static, T: 'static, I>(
+        &self,
+        entity: E,
+        columns: I,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        T: ColumnTrait,
+        I: IntoIterator<Item = T>,
+    {
+        let backend = self.db.get_database_backend();
+        let columns = columns.into_iter().collect::<Vec<_>>();
+        let name = self.naming_convention().unique(
+            entity.table_name(),
+            &columns.iter().map(|c| c.as_str()).collect::<Vec<_>>(),
+        );
+
+        if backend == DbBackend::Sqlite {
+            let mut stmt = Index::create();
+            stmt.name(&name).table(entity).unique();
+            for column in columns {
+                stmt.col(column);
+            }
+            return self.db.execute(build_schema_stmt(backend, &stmt)).await;
+        }
+
+        self.execute_sql(format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} UNIQUE ({})",
+            quote_iden(backend, &entity),
+            quote_iden(backend, &Alias::new(&name)),
+            columns
+                .iter()
+                .map(|column| quote_iden(backend, column))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .await
+    }
+
+    /// drop_constraint will drop a check or unique constraint from the database table of a SeaORM Entity using the name of the constraint. Dropping constraints on MySQL requires MySQL 8.0.19 or later.
+    /// On SQLite the table is rebuilt without the constraint unless it is a unique index created by `add_unique_constraint` which is dropped directly.
+    /// Refer to `add_check_constraint` for an example.
+    pub async fn drop_constraint<E: 'static>(
+        &self,
+        entity: E,
+        name: &str,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait,
+    {
+        let backend = self.db.get_database_backend();
+        if backend == DbBackend::Sqlite {
+            let is_index = self
+                .db
+                .query_one(Statement::from_sql_and_values(
+                    backend,
+                    "SELECT 1 FROM sqlite_master WHERE type = 'index' AND tbl_name = ? AND name = ?",
+                    vec![entity.table_name().into(), name.into()],
+                ))
+                .await?
+                .is_some();
+            if is_index {
+                return self
+                    .execute_sql(format!(
+                        "DROP INDEX {}",
+                        quote_iden(backend, &Alias::new(name))
+                    ))
+                    .await;
+            }
+
+            return sqlite::alter_table_definitions(self.db, entity.table_name(), |definitions| {
+                let count = definitions.len();
+                definitions.retain(|definition| {
+                    sqlite::constraint_name(definition).as_deref() != Some(name)
+                });
+                if definitions.len() == count {
+                    return Err(DbErr::Custom(format!(
+                        "The constraint '{}' does not exist on the table '{}'!",
+                        name,
+                        entity.table_name()
+                    )));
+                }
+                Ok(())
+            })
+            .await;
+        }
+
+        self.execute_sql(format!(
+            "ALTER TABLE {} DROP CONSTRAINT {}",
+            quote_iden(backend, &entity),
+            quote_iden(backend, &Alias::new(name))
+        ))
+        .await
+    }
+
+    // existing_foreign_keys reads the foreign keys of a database table and returns a function which reports whether a foreign key of a TableSchema is one of them. It is used so rebuilding a SQLite table from an Entity doesn't add foreign keys for Relations which haven't been added yet.
+    async fn existing_foreign_keys(
+        &self,
+        table_name: &str,
+    ) -> Result<impl Fn(&ForeignKeySchema) -> bool, DbErr> {
+        let existing = introspect_table(self.db, table_name)
+            .await?
+            .map(|table| table.foreign_keys)
+            .unwrap_or_default();
+
+        Ok(move |foreign_key: &ForeignKeySchema| {
+            existing.iter().any(|existing| {
+                existing.columns == foreign_key.columns
+                    && existing.referenced_table == foreign_key.referenced_table
+            })
+        })
+    }
+
+    // rebuild_sqlite_table rebuilds a table on SQLite so it matches a TableSchema. This is used to make changes SQLite's `ALTER TABLE` doesn't support.
+    // Columns and foreign keys for which the filter functions return false are left out of the new table along with the indexes of the columns left out. `renamed_column` can be used to copy the data of a column (named by the second value) under a previous name (the first value).
+    // Columns without a default in the TableSchema keep the default they have in the existing table (as Entities can't describe defaults) except for the `altered_column` which always uses the default from the TableSchema.
+    async fn rebuild_sqlite_table(
+        &self,
+        table: &TableSchema,
+        include_column: impl Fn(&ColumnSchema) -> bool,
+        include_foreign_key: impl Fn(&ForeignKeySchema) -> bool,
+        renamed_column: Option<(String, String)>,
+        altered_column: Option<&str>,
+    ) -> Result<ExecResult, DbErr> {
+        let ctx = self.schema_context();
+        let naming = ctx.naming;
+        let existing_defaults = sqlite::column_defaults(self.db, &table.name).await?;
+        let columns = table
+            .columns
+            .iter()
+            .filter(|column| include_column(column))
+            .map(|column| {
+                let mut column = column.clone();
+                let old_name = match &renamed_column {
+                    Some((old_name, renamed)) if *renamed == column.name => old_name,
+                    _ => &column.name,
+                };
+                if column.default.is_none() && altered_column != Some(column.name.as_str()) {
+                    column.default = existing_defaults
+                        .iter()
+                        .find(|(name, _)| name == old_name)
+                        .map(|(_, default)| ColumnDefault::expr(default.as_str()));
+                }
+                column
+            })
+            .collect::<Vec<_>>();
+        let indexes = table
+            .indexes
+            .iter()
+            .filter(|index| {
+                index
+                    .columns
+                    .iter()
+                    .all(|name| columns.iter().any(|column| column.name == *name))
+            })
+            .cloned()
+            .collect();
+        let new_table = TableSchema {
+            columns,
+            indexes,
+            foreign_keys: table
+                .foreign_keys
+                .iter()
+                .filter(|foreign_key| include_foreign_key(foreign_key))
+                .cloned()
+                .collect(),
+            ..table.clone()
+        };
+        let create_stmt = get_schema_create_def(&new_table, &ctx);
+        let index_stmts = get_schema_index_defs(&new_table, &ctx, |_| true);
+        let mut managed_indexes = get_managed_index_names(table, naming);
+        if let Some((old_name, _)) = &renamed_column {
+            managed_indexes.push(naming.unique(&table.name, &[old_name.as_str()]));
+            managed_indexes.push(naming.index(&table.name, &[old_name.as_str()]));
+        }
+
+        let column_mapping = new_table
+            .columns
+            .iter()
+            .map(|column| {
+                let new_name = column.name.clone();
+                match &renamed_column {
+                    Some((old_name, renamed)) if *renamed == column.name => {
+                        (new_name, old_name.clone())
+                    }
+                    _ => (new_name.clone(), new_name),
+                }
+            })
+            .collect();
+
+        sqlite::rebuild_table(
+            self.db,
+            &table.name,
+            create_stmt,
+            column_mapping,
+            index_stmts,
+            managed_indexes,
+        )
+        .await
+    }
+
+    /// create_view will create a database view with the given name. The body of the view is a sea_query `SelectStatement` which is rendered for the database backend being used.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_orm::sea_query::{Alias, Expr, Query};
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod cake {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///         pub price: i32,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(cake::Entity).await?;
+    ///
+    ///     let cheap_cakes = Query::select()
+    ///         .columns([cake::Column::Id, cake::Column::Name])
+    ///         .from(cake::Entity)
+    ///         .and_where(Expr::col(cake::Column::Price).lt(10))
+    ///         .to_owned();
+    ///     mg.create_view(Alias::new("cheap_cakes"), cheap_cakes).await?;
+    ///
+    ///     let all_cakes = Query::select()
+    ///         .columns([cake::Column::Id, cake::Column::Name])
+    ///         .from(cake::Entity)
+    ///         .to_owned();
+    ///     mg.replace_view(Alias::new("cheap_cakes"), all_cakes).await?;
+    ///
+    ///     mg.drop_view(Alias::new("cheap_cakes")).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_view<V: 'static>(
+        &self,
+        name: V,
+        select: SelectStatement,
+    ) -> Result<ExecResult, DbErr>
+    where
+        V: Iden,
+    {
+        let backend = self.db.get_database_backend();
+        self.execute_sql(format!(
+            "CREATE VIEW {} AS {}",
+            quote_iden(backend, &name),
+            build_query_sql(backend, &select)
+        ))
+        .await
+    }
+
+    /// replace_view will replace the body of a database view or create the view if it does not exist. SQLite can't replace views so on SQLite the view is dropped and created again.
+    /// Refer to `create_view` for an example.
+    pub async fn replace_view<V: 'static>(
+        &self,
+        name: V,
+        select: SelectStatement,
+    ) -> Result<ExecResult, DbErr>
+    where
+        V: Iden,
+    {
+        let backend = self.db.get_database_backend();
+        if backend == DbBackend::Sqlite {
+            self.execute_sql(format!(
+                "DROP VIEW IF EXISTS {}",
+                quote_iden(backend, &name)
+            ))
+            .await?;
+            return self.create_view(name, select).await;
+        }
+
+        self.execute_sql(format!(
+            "CREATE OR REPLACE VIEW {} AS {}",
+            quote_iden(backend, &name),
+            build_query_sql(backend, &select)
+        ))
+        .await
+    }
+
+    /// drop_view will drop a database view if it exists.
+    /// Refer to `create_view` for an example.
+    pub async fn drop_view<V: 'static>(&self, name: V) -> Result<ExecResult, DbErr>
+    where
+        V: Iden,
+    {
+        let backend = self.db.get_database_backend();
+        self.execute_sql(format!(
+            "DROP VIEW IF EXISTS {}",
+            quote_iden(backend, &name)
+        ))
+        .await
+    }
+
+    /// create_materialized_view will create a materialized view with the given name. The results of the `SelectStatement` are stored and only updated when `refresh_materialized_view` is called.
+    /// Materialized views are only supported on Postgres so an error is returned on other databases.
+    pub async fn create_materialized_view<V: 'static>(
+        &self,
+        name: V,
+        select: SelectStatement,
+    ) -> Result<ExecResult, DbErr>
+    where
+        V: Iden,
+    {
+        let backend = self.postgres_only("Materialized views")?;
+        self.execute_sql(format!(
+            "CREATE MATERIALIZED VIEW {} AS {}",
+            quote_iden(backend, &name),
+            build_query_sql(backend, &select)
+        ))
+        .await
+    }
+
+    /// refresh_materialized_view will rerun the query of a materialized view and store the new results. Materialized views are only supported on Postgres so an error is returned on other databases.
+    pub async fn refresh_materialized_view<V: 'static>(&self, name: V) -> Result<ExecResult, DbErr>
+    where
+        V: Iden,
+    {
+        let backend = self.postgres_only("Materialized views")?;
+        self.execute_sql(format!(
+            "REFRESH MATERIALIZED VIEW {}",
+            quote_iden(backend, &name)
+        ))
+        .await
+    }
+
+    /// drop_materialized_view will drop a materialized view if it exists. Materialized views are only supported on Postgres so an error is returned on other databases.
+    pub async fn drop_materialized_view<V: 'static>(&self, name: V) -> Result<ExecResult, DbErr>
+    where
+        V: Iden,
+    {
+        let backend = self.postgres_only("Materialized views")?;
+        self.execute_sql(format!(
+            "DROP MATERIALIZED VIEW IF EXISTS {}",
+            quote_iden(backend, &name)
+        ))
+        .await
+    }
+
+    // postgres_only returns an error if the database is not Postgres. It is used by the helpers for features which only exist on Postgres.
+    fn postgres_only(&self, feature: &str) -> Result<DbBackend, DbErr> {
+        match self.db.get_database_backend() {
+            DbBackend::Postgres => Ok(DbBackend::Postgres),
+            backend => Err(DbErr::Custom(format!(
+                "{} are only supported on Postgres but the database is {:?}!",
+                feature, backend
+            ))),
+        }
+    }
+
+    /// execute_backend_sql will run the variant of some SQL for the database backend being used. An error is returned if there is no variant for the database backend.
+    /// This is useful for migrations which need different SQL on each database such as creating a full-text index.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_migrations::{BackendSql, MigrationManager};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///
+    ///     mg.execute_backend_sql(
+    ///         &BackendSql::new()
+    ///             .postgres("CREATE TABLE settings (data JSONB NOT NULL)")
+    ///             .mysql("CREATE TABLE settings (data JSON NOT NULL)")
+    ///             .sqlite("CREATE TABLE settings (data TEXT NOT NULL)"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn execute_backend_sql(&self, sql: &BackendSql) -> Result<ExecResult, DbErr> {
+        self.execute_sql(sql.get(self.db.get_database_backend())?.to_string())
+            .await
+    }
+
+    /// create_function will create a stored function or procedure. The `definition` is the complete `CREATE FUNCTION` (or `CREATE PROCEDURE`) statement for each database backend. SQLite doesn't have stored functions.
+    /// Refer to `create_trigger` for an example.
+    pub async fn create_function(&self, definition: &BackendSql) -> Result<ExecResult, DbErr> {
+        self.execute_backend_sql(definition).await
+    }
+
+    /// drop_function will drop a stored function if it exists. Stored functions don't exist on SQLite so an error is returned on SQLite.
+    pub async fn drop_function(&self, name: &str) -> Result<ExecResult, DbErr> {
+        let backend = self.db.get_database_backend();
+        if backend == DbBackend::Sqlite {
+            return Err(DbErr::Custom(
+                "Stored functions are not supported on SQLite!".into(),
+            ));
+        }
+
+        self.execute_sql(format!(
+            "DROP FUNCTION IF EXISTS {}",
+            quote_iden(backend, &Alias::new(name))
+        ))
+        .await
+    }
+
+    /// create_trigger will create a row level trigger on the database table of a SeaORM Entity. The `body` contains the statements run by the trigger for each database backend:
+    /// - Postgres: the PL/pgSQL statements of a trigger function named `<name>_fn` which is created with the trigger. They must end with `RETURN NEW;` (or `RETURN OLD;`).
+    /// - MySQL and SQLite: the statements run for each row. They are wrapped in `BEGIN ... END`.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_migrations::{BackendSql, MigrationManager, TriggerEvent, TriggerTiming};
+    ///
+    /// mod cake {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(cake::Entity).await?;
+    ///
+    ///     mg.create_trigger(
+    ///         cake::Entity,
+    ///         "trg_cake_name",
+    ///         TriggerTiming::Before,
+    ///         TriggerEvent::Insert,
+    ///         &BackendSql::new()
+    ///             .postgres("NEW.name = TRIM(NEW.name); RETURN NEW;")
+    ///             .mysql("SET NEW.name = TRIM(NEW.name);")
+    ///             .sqlite("SELECT RAISE(ABORT, 'name is empty') WHERE TRIM(NEW.name) = '';"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     mg.drop_trigger(cake::Entity, "trg_cake_name").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_trigger<E: 'static>(
+        &self,
+        entity: E,
+        name: &str,
+        timing: TriggerTiming,
+        event: TriggerEvent,
+        body: &BackendSql,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait,
+    {
+        let backend = self.db.get_database_backend();
+        let body = body.get(backend)?;
+        let trigger = format!(
+            "CREATE TRIGGER {} {} {} ON {} FOR EACH ROW",
+            quote_iden(backend, &Alias::new(name)),
+            timing.as_sql(),
+            event.as_sql(),
+            quote_iden(backend, &entity)
+        );
+
+        match backend {
+            DbBackend::Postgres => {
+                let function_name = quote_iden(backend, &Alias::new(&format!("{}_fn", name)));
+                self.execute_sql(format!(
+                    "CREATE OR REPLACE FUNCTION {}() RETURNS trigger AS $$ BEGIN {} END; $$ LANGUAGE plpgsql",
+                    function_name, body
+                ))
+                .await?;
+                self.execute_sql(format!("{} EXECUTE PROCEDURE {}()", trigger, function_name))
+                    .await
+            }
+            DbBackend::MySql | DbBackend::Sqlite => {
+                self.execute_sql(format!("{} BEGIN {} END", trigger, body))
+                    .await
+            }
+        }
+    }
+
+    /// drop_trigger will drop a trigger from the database table of a SeaORM Entity if it exists. On Postgres the trigger function created by `create_trigger` is also dropped.
+    /// Refer to `create_trigger` for an example.
+    pub async fn drop_trigger<E: 'static>(&self, entity: E, name: &str) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait,
+    {
+        let backend = self.db.get_database_backend();
+        let trigger_name = quote_iden(backend, &Alias::new(name));
+        match backend {
+            DbBackend::Postgres => {
+                self.execute_sql(format!(
+                    "DROP TRIGGER IF EXISTS {} ON {}",
+                    trigger_name,
+                    quote_iden(backend, &entity)
+                ))
+                .await?;
+                self.execute_sql(format!(
+                    "DROP FUNCTION IF EXISTS {}()",
+                    quote_iden(backend, &Alias::new(&format!("{}_fn", name)))
+                ))
+                .await
+            }
+            DbBackend::MySql | DbBackend::Sqlite => {
+                self.execute_sql(format!("DROP TRIGGER IF EXISTS {}", trigger_name))
+                    .await
+            }
+        }
+    }
+
+    /// add_updated_at_trigger will create a trigger which sets a Column on a SeaORM Entity to the current time whenever a row in it's database table is updated. The trigger is named using [NamingConvention::trigger].
+    /// SQLite triggers can't modify the row being updated so on SQLite the trigger updates the row again after it has been updated.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod cake {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///         #[sea_orm(column_type = "DateTime")]
+    ///         pub updated_at: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(cake::Entity).await?;
+    ///
+    ///     mg.add_updated_at_trigger(cake::Entity, cake::Column::UpdatedAt).await?; // Replace "cake" with the name of the module containing your SeaORM Model and UpdatedAt with the name of your Column.
+    ///     mg.drop_updated_at_trigger(cake::Entity, cake::Column::UpdatedAt).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn add_updated_at_trigger<E: 'static, T: 'static>(
+        &self,
+        entity: E,
+        column: T,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        T: ColumnTrait,
+    {
+        let backend = self.db.get_database_backend();
+        let name = self
+            .naming_convention()
+            .trigger(entity.table_name(), &[column.as_str()]);
+        let column_name = quote_iden(backend, &column);
+
+        let (timing, body) = match backend {
+            DbBackend::Postgres => (
+                TriggerTiming::Before,
+                BackendSql::new().postgres(format!(
+                    "NEW.{} = CURRENT_TIMESTAMP; RETURN NEW;",
+                    column_name
+                )),
+            ),
+            DbBackend::MySql => (
+                TriggerTiming::Before,
+                BackendSql::new().mysql(format!("SET NEW.{} = CURRENT_TIMESTAMP;", column_name)),
+            ),
+            // Recursive triggers are disabled by default in SQLite so the update doesn't run the trigger again.
+            DbBackend::Sqlite => (
+                TriggerTiming::After,
+                BackendSql::new().sqlite(format!(
+                    "UPDATE {} SET {} = CURRENT_TIMESTAMP WHERE rowid = NEW.rowid;",
+                    quote_iden(backend, &entity),
+                    column_name
+                )),
+            ),
+        };
+
+        self.create_trigger(entity, &name, timing, TriggerEvent::Update, &body)
+            .await
+    }
+
+    /// drop_updated_at_trigger will drop the trigger created by `add_updated_at_trigger` for a Column on a SeaORM Entity.
+    /// Refer to `add_updated_at_trigger` for an example.
+    pub async fn drop_updated_at_trigger<E: 'static, T: 'static>(
+        &self,
+        entity: E,
+        column: T,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        T: ColumnTrait,
+    {
+        let name = self
+            .naming_convention()
+            .trigger(entity.table_name(), &[column.as_str()]);
+        self.drop_trigger(entity, &name).await
+    }
+
+    /// rename_table will rename an existing database table to the table name of a SeaORM Entity. The old table can be referred to using the SeaORM Entity it was previously defined by or by it's name using a sea_query `Alias`.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_orm::sea_query::Alias;
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod original_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// mod updated_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "dessert")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(original_model::Entity).await?; // Create the original table. This would have been done in the previous version of your application.
+    ///
+    ///     mg.rename_table(original_model::Entity, updated_model::Entity).await?; // The old table could also be referred to by name using `Alias::new("cake")`.
+    ///     mg.rename_table(Alias::new("dessert"), original_model::Entity).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rename_table<O: 'static, E: 'static>(
+        &self,
+        old_table: O,
+        entity: E,
+    ) -> Result<ExecResult, DbErr>
+    where
+        O: Iden,
+        E: EntityTrait,
+    {
+        let stmt = Table::rename().table(old_table, entity).to_owned();
+        self.db
+            .execute(self.db.get_database_backend().build(&stmt))
+            .await
+    }
+
+    /// rename_column will rename an existing column in the database table of a SeaORM Entity to the name of a Column on the Entity. The old column is referred to by it's name using a sea_query `Alias`.
+    /// SQLite only supports renaming columns since version 3.25.0 so on older versions of SQLite the table is rebuilt from the Entity instead.
+    ///
+    /// ```rust
+    /// use sea_orm::{Database, DbErr};
+    /// use sea_orm::sea_query::Alias;
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod original_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// mod updated_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub title: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(original_model::Entity).await?; // Create the original table. This would have been done in the previous version of your application.
+    ///
+    ///     mg.rename_column(updated_model::Entity, Alias::new("name"), updated_model::Column::Title).await?; // Replace "updated_model" with the name of the module containing your SeaORM Model and Title with the new Column.
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rename_column<E: 'static, O: 'static, T: 'static>(
+        &self,
+        entity: E,
+        old_column: O,
+        column: T,
+    ) -> Result<ExecResult, DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        O: Iden,
+        T: ColumnTrait,
+    {
+        if self.db.get_database_backend() == DbBackend::Sqlite
+            && !sqlite::supports_rename_column(self.db).await?
+        {
+            return self
+                .rebuild_sqlite_table(
+                    &TableSchema::from_entity(entity),
+                    |_| true,
+                    |_| true,
+                    Some((old_column.to_string(), column.as_str().to_string())),
+                    None,
+                )
+                .await;
+        }
+
+        let mut stmt = Table::alter();
+        stmt.table(entity).rename_column(old_column, column);
+
+        self.db
+            .execute(self.db.get_database_backend().build(&stmt))
+            .await
+    }
+
+    /// alter_column will update an existing column in the database table so it's type, nullability, uniqueness and index match the definition of a Column on a SeaORM Entity. Any default value of the column is removed, use `alter_column_with_default` to set one.
+    /// SQLite can't alter columns so on SQLite the table is rebuilt from the Entity instead. The Entity must describe every column of the table for this to work.
+    ///
+    /// ```rust
+    /// use sea_orm::{ConnectionTrait, Database, DbBackend, DbErr, Statement};
+    /// use sea_migrations::MigrationManager;
+    ///
+    /// mod original_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// mod updated_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         #[sea_orm(column_type = "Text", nullable)]
+    ///         pub name: Option<String>,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(original_model::Entity).await?; // Create the original table. This would have been done in the previous version of your application.
+    ///     // Views using the table are kept when it is rebuilt.
+    ///     db.execute(Statement::from_string(DbBackend::Sqlite, "CREATE VIEW cake_names AS SELECT name FROM cake".into())).await?;
+    ///
+    ///     mg.alter_column(updated_model::Entity, updated_model::Column::Name).await?; // Replace "updated_model" with the name of the module containing your SeaORM Model and Name with the name of the Column to alter.
+    ///
+    ///     db.query_all(Statement::from_string(DbBackend::Sqlite, "SELECT name FROM cake_names".into())).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn alter_column<E: 'static, T: 'static>(
+        &self,
+        entity: E,
+        column: T,
+    ) -> Result<(), DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        T: ColumnTrait,
+    {
+        self.alter_column_def(entity, column, None).await
+    }
+
+    /// alter_column_with_default will update an existing column in the database table like `alter_column` and set it's default value. The default is used for rows inserted without a value for the column.
+    ///
+    /// ```rust
+    /// use sea_orm::{ConnectionTrait, Database, DbBackend, DbErr, Statement};
+    /// use sea_migrations::{ColumnDefault, MigrationManager};
+    ///
+    /// mod original_model {
+    ///     use sea_orm::entity::prelude::*;
+    ///
+    ///     #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    ///     #[sea_orm(table_name = "cake")]
+    ///     pub struct Model {
+    ///         #[sea_orm(primary_key)]
+    ///         pub id: i32,
+    ///         pub name: String,
+    ///         pub topping: Option<String>,
+    ///     }
+    ///
+    ///     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    ///     pub enum Relation {}
+    ///
+    ///     impl ActiveModelBehavior for ActiveModel {}
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+    ///     let mg = MigrationManager::new(&db);
+    ///     mg.create_table(original_model::Entity).await?; // Create the original table. This would have been done in the previous version of your application.
+    ///
+    ///     mg.alter_column_with_default(original_model::Entity, original_model::Column::Name, ColumnDefault::value("Sponge")).await?; // Replace "original_model" with the name of the module containing your SeaORM Model and Name with the name of the Column to alter.
+    ///     // The default is kept when another column is altered.
+    ///     mg.alter_column(original_model::Entity, original_model::Column::Topping).await?;
+    ///
+    ///     db.execute(Statement::from_string(DbBackend::Sqlite, "INSERT INTO cake DEFAULT VALUES".into())).await?;
+    ///     let row = db.query_one(Statement::from_string(DbBackend::Sqlite, "SELECT name FROM cake".into())).await?.unwrap();
+    ///     assert_eq!(row.try_get::<String>("", "name")?, "Sponge");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn alter_column_with_default<E: 'static, T: 'static>(
+        &self,
+        entity: E,
+        column: T,
+        default: ColumnDefault,
+    ) -> Result<(), DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        T: ColumnTrait,
+    {
+        self.alter_column_def(entity, column, Some(default)).await
+    }
+
+    // alter_column_def alters a column of an Entity and sets or removes it's default value. It is used to implement `alter_column` and `alter_column_with_default`.
+    async fn alter_column_def<E: 'static, T: 'static>(
+        &self,
+        entity: E,
+        column: T,
+        default: Option<ColumnDefault>,
+    ) -> Result<(), DbErr>
+    where
+        E: EntityTrait<Column = T>,
+        T: ColumnTrait,
+    {
+        let backend = self.db.get_database_backend();
+        let column_def_prelude = get_column_prelude(column);
+        let is_primary_key = E::PrimaryKey::from_column(column).is_some();
+        let table_name = quote_iden(backend, &entity);
+        let column_name = quote_iden(backend, &column);
+
+        match backend {
+            DbBackend::Postgres => {
+                if let ColumnType::Enum(name, values) = &column_def_prelude.col_type {
+                    // Existing values must be explicitly cast to convert a column to an enum type.
+                    self.create_enum_type(name, values).await?;
+                    let enum_type = quote_iden(backend, &Alias::new(name));
+                    self.execute_sql(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::text::{}",
+                        table_name, column_name, enum_type, column_name, enum_type
+                    ))
+                    .await?;
+                } else {
+                    let mut stmt = Table::alter();
+                    stmt.table(entity)
+                        .modify_column(&mut get_column_type_def::<E>(column, backend));
+                    self.db.execute(backend.build(&stmt)).await?;
+                }
+
+                self.execute_sql(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} {} NOT NULL",
+                    table_name,
+                    column_name,
+                    if column_def_prelude.null {
+                        "DROP"
+                    } else {
+                        "SET"
+                    }
+                ))
+                .await?;
+
+                self.execute_sql(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} {}",
+                    table_name,
+                    column_name,
+                    match &default {
+                        Some(default) => format!("SET DEFAULT {}", default.to_sql(backend)),
+                        None => "DROP DEFAULT".into(),
+                    }
+                ))
+                .await?;
+
+                if !is_primary_key {
+                    // Tables created by older versions of sea_migrations used unique constraints which Postgres named `<table>_<column>_key`.
+                    let legacy_constraint_name = quote_iden(
+                        backend,
+                        &Alias::new(&format!("{}_{}_key", entity.table_name(), column.as_str())),
+                    );
+                    self.execute_sql(format!(
+                        "ALTER TABLE {} DROP CONSTRAINT IF EXISTS {}",
+                        table_name, legacy_constraint_name
+                    ))
+                    .await?;
+                }
+            }
+            DbBackend::MySql => {
+                let ctx = self.schema_context();
+                let mut column_def = get_column_type_def::<E>(column, backend);
+                if !column_def_prelude.null {
+                    column_def.not_null();
+                }
+                if is_auto_increment_column::<E>(column) {
+                    column_def.auto_increment();
+                }
+
+                // The check constraint of an enum column must be replaced as it contains the values of the enum.
+                let check_name = ctx.naming.check(entity.table_name(), &[column.as_str()]);
+                let check_exists = self
+                    .db
+                    .query_one(Statement::from_sql_and_values(
+                        backend,
+                        "SELECT 1 FROM information_schema.table_constraints WHERE table_schema = DATABASE() AND table_name = ? AND constraint_name = ? AND constraint_type = 'CHECK'",
+                        vec![entity.table_name().into(), check_name.clone().into()],
+                    ))
+                    .await?
+                    .is_some();
+                if check_exists {
+                    self.execute_sql(format!(
+                        "ALTER TABLE {} DROP CHECK {}",
+                        table_name,
+                        quote_iden(backend, &Alias::new(&check_name))
+                    ))
+                    .await?;
+                }
+                if let ColumnType::Enum(_, values) = &column_def_prelude.col_type {
+                    column_def.extra(get_enum_check_constraint(
+                        &ctx,
+                        entity.table_name(),
+                        column.as_str(),
+                        values,
+                    ));
+                }
+
+                let mut stmt = Table::alter();
+                stmt.table(entity).modify_column(&mut column_def);
+                self.db.execute(backend.build(&stmt)).await?;
+
+                // MODIFY COLUMN replaces the whole definition of the column so any previous default has already been removed.
+                if let Some(default) = &default {
+                    let default_sql = match default {
+                        ColumnDefault::Value(_) => default.to_sql(backend),
+                        // MySQL only accepts expressions as a default when they are wrapped in parentheses.
+                        ColumnDefault::Expr(expr) => format!("({})", expr),
+                    };
+                    self.execute_sql(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {}",
+                        table_name, column_name, default_sql
+                    ))
+                    .await?;
+                }
+
+                if !is_primary_key {
+                    // Tables created by older versions of sea_migrations used unique indexes which MySQL named after the column.
+                    self.drop_index_if_exists(entity, column.as_str()).await?;
+                }
+            }
+            DbBackend::Sqlite => {
+                let mut table = TableSchema::from_entity(entity);
+                if let Some(altered) = table.columns.iter_mut().find(|c| c.name == column.as_str())
+                {
+                    altered.default = default;
+                }
+                self.rebuild_sqlite_table(&table, |_| true, |_| true, None, Some(column.as_str()))
+                    .await?;
+                return Ok(());
+            }
+        }
+
+        if !is_primary_key {
+            let ctx = self.schema_context();
+            let naming = ctx.naming;
+            let columns = [column.as_str()];
+            self.drop_index_if_exists(entity, &naming.unique(entity.table_name(), &columns))
+                .await?;
+            self.drop_index_if_exists(entity, &naming.index(entity.table_name(), &columns))
+                .await?;
+
+            let table = TableSchema::from_entity(entity);
+            for stmt in
+                get_schema_index_defs(&table, &ctx, |index| index.columns == [column.as_str()])
+            {
+                self.db.execute(build_schema_stmt(backend, &stmt)).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// RollbackPolicy controls what the Migrator does when the `up` function of a migration returns an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RollbackPolicy {
+    /// RunDown will run the `down` function of the failed migration to undo any partially applied changes. This is the default.
+    #[default]
+    RunDown,
+    /// Skip will leave the database as the failed `up` function left it. This is useful when you would rather inspect the database by hand than have it modified further.
+    Skip,
+}
+
+/// PostgresAutoIncrement controls how auto incrementing primary keys are created on Postgres.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PostgresAutoIncrement {
+    /// Serial creates the column using the `smallserial`, `serial` or `bigserial` type matching the integer type of the column. This is the default.
+    #[default]
+    Serial,
+    /// Identity creates the column as a `GENERATED BY DEFAULT AS IDENTITY` column. Identity columns are the SQL standard way of auto incrementing a column and are recommended by Postgres 10 and later.
+    Identity,
+}
+
+/// MigratorOptions is used to configure how the Migrator runs migrations.
+#[derive(Debug, Clone)]
+pub struct MigratorOptions {
+    /// rollback_policy controls whether the `down` function of a migration is run when its `up` function fails.
+    pub rollback_policy: RollbackPolicy,
+    /// naming_convention is used by the MigrationManager to name the constraints and indexes it creates.
+    pub naming_convention: Arc<dyn NamingConvention>,
+    /// postgres_auto_increment controls whether auto incrementing primary keys are created as serial or identity columns on Postgres. It has no effect on other databases.
+    pub postgres_auto_increment: PostgresAutoIncrement,
+    /// lock_timeout is how long the lock on the migrations table is held without being refreshed before another process may take it over. The lock is refreshed before each migration is run so it must be longer than the slowest migration.
+    /// A lock is only left behind without being refreshed when the process running the migrations was cancelled or killed. The default is 15 minutes.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use sea_migrations::{Migrator, MigratorOptions};
+    /// use sea_orm::{ConnectionTrait, Database, DbErr, Statement};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     Migrator::run(&db, &mut vec![]).await?;
+    ///
+    ///     // A lock left behind by a process which was killed half an hour ago.
+    ///     db.execute(Statement::from_string(
+    ///         db.get_database_backend(),
+    ///         "INSERT INTO _sea_migrations (version, owner, locked_at) VALUES ('_lock', 'killed', CAST(strftime('%s', 'now') AS INTEGER) - 30 * 60)".into(),
+    ///     ))
+    ///     .await?;
+    ///
+    ///     let options = MigratorOptions {
+    ///         lock_timeout: Duration::from_secs(60 * 60),
+    ///         ..Default::default()
+    ///     };
+    ///     assert!(Migrator::run_with_options(&db, &mut vec![], options).await.is_err());
+    ///
+    ///     // The lock has expired using the default timeout so it is taken over.
+    ///     Migrator::run(&db, &mut vec![]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub lock_timeout: Duration,
+}
+
+impl Default for MigratorOptions {
+    fn default() -> Self {
+        Self {
+            rollback_policy: RollbackPolicy::default(),
+            naming_convention: Arc::new(DefaultNamingConvention),
+            postgres_auto_increment: PostgresAutoIncrement::default(),
+            lock_timeout: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+/// MigrationState is the state of a migration in the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    /// Applied is a migration which has been run on the database.
+    Applied,
+    /// Pending is a migration which has not been run on the database yet.
+    Pending,
+    /// Missing is a migration which has been run on the database but was not provided to the Migrator. This normally means the migration was deleted or renamed.
+    Missing,
+}
+
+/// MigrationStatus is returned by `Migrator::status` for each migration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// name is the name of the migration.
+    pub name: String,
+    /// state is whether the migration has been run on the database.
+    pub state: MigrationState,
+}
+
+/// Migrator is used to handle running migration operations.
+pub struct Migrator;
+
+impl Migrator {
+    /// run will run all of the database migrations provided via the migrations parameter.
+    /// In microservice environments think about how this function is called. It contains an internal lock to prevent multiple clients running migrations at the same time but don't rely on it!
+    /// The lock is released when the migrations finish, fail or panic. The lock can't be released if the returned future is dropped before completing (eg. due to a timeout) as no async code can be run at that point. Instead the lock row is left behind until it expires after `MigratorOptions::lock_timeout` and is then taken over by the next process to run the migrations. It can be removed sooner using `Migrator::force_unlock`.
+    ///
+    /// ```rust
+    /// use sea_migrations::Migrator;
+    /// use sea_orm::{ Database, DbErr };
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     
+    ///     Migrator::run(
+    ///         &db,
+    ///         &mut vec![
+    ///            // Box::new(models::M20210101020202DoAThing),
+    ///         ],
+    ///     )
+    ///     .await?;
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// ```
+    // Note(oscar): I don't like that the migrations argument is mutable but it works for now and that argument will be removed in a future version so their is no point trying to fix it.
     pub async fn run(
         db: &DbConn,
         migrations: &mut Vec<Box<dyn MigratorTrait>>,
     ) -> Result<(), DbErr> {
-        let mg = MigrationManager::new(db);
+        Ok(Self::run_with_options(db, migrations, MigratorOptions::default()).await?)
+    }
+
+    /// run_with_options will run all of the database migrations provided via the migrations parameter using the provided options.
+    /// Unlike `run`, which converts errors into a `DbErr` to stay compatible with existing callers, a `MigrationError` is returned so the failing migration and the errors of its `up` and `down` functions can be inspected.
+    ///
+    /// ```rust
+    /// use sea_migrations::{Migrator, MigratorOptions, RollbackPolicy};
+    /// use sea_orm::{ Database, DbErr };
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///
+    ///     Migrator::run_with_options(
+    ///         &db,
+    ///         &mut vec![
+    ///            // Box::new(models::M20210101020202DoAThing),
+    ///         ],
+    ///         MigratorOptions {
+    ///             rollback_policy: RollbackPolicy::Skip,
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_with_options(
+        db: &DbConn,
+        migrations: &mut Vec<Box<dyn MigratorTrait>>,
+        options: MigratorOptions,
+    ) -> Result<(), MigrationError> {
+        let mg = MigrationManager::with_options(db, &options);
+        migrations_table::init(db).await?;
+        let lock = migrations_table::lock(db, options.lock_timeout).await?;
+        let result = Self::do_migrations(&mg, &lock, migrations, &options).await;
+        let unlock_result = lock.release().await;
+        result?;
+        unlock_result?;
+        Ok(())
+    }
+
+    /// down will undo the last `steps` migrations that were applied to the database by running their `down` function in reverse order. Use `usize::MAX` to undo every migration.
+    /// An error is returned if an applied migration was not provided via the migrations parameter as it can't be undone.
+    ///
+    /// ```rust
+    /// use sea_migrations::Migrator;
+    /// use sea_orm::{ Database, DbErr };
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///
+    ///     Migrator::down(
+    ///         &db,
+    ///         &[
+    ///            // Box::new(models::M20210101020202DoAThing),
+    ///         ],
+    ///         1,
+    ///     )
+    ///     .await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn down(
+        db: &DbConn,
+        migrations: &[Box<dyn MigratorTrait>],
+        steps: usize,
+    ) -> Result<(), MigrationError> {
+        Self::down_with_options(db, migrations, steps, MigratorOptions::default()).await
+    }
+
+    /// down_with_options will undo the last `steps` migrations that were applied to the database using the provided options. Refer to `down` for more information.
+    pub async fn down_with_options(
+        db: &DbConn,
+        migrations: &[Box<dyn MigratorTrait>],
+        steps: usize,
+        options: MigratorOptions,
+    ) -> Result<(), MigrationError> {
+        let mg = MigrationManager::with_options(db, &options);
+        migrations_table::init(db).await?;
+        let lock = migrations_table::lock(db, options.lock_timeout).await?;
+        let result = Self::undo_migrations(&mg, &lock, migrations, steps).await;
+        let unlock_result = lock.release().await;
+        result?;
+        unlock_result?;
+        Ok(())
+    }
+
+    /// redo will undo the last migration that was applied to the database and then apply it again. This is useful while writing a migration to check that its `down` function works.
+    /// Only the reverted migration is applied again, even if other migrations are pending, and the migrations table stays locked in between so no other process can run migrations half way through. The name of the migration is returned or None if no migrations have been applied.
+    ///
+    /// ```rust
+    /// use sea_migrations::{Migrator, MigratorTrait, SqlMigration};
+    /// use sea_orm::{ConnectionTrait, Database, DbErr, Statement};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///     let bakery = || -> Box<dyn MigratorTrait> {
+    ///         Box::new(SqlMigration::new(
+    ///             "M20210101020202CreateBakery",
+    ///             "CREATE TABLE bakery (id INTEGER PRIMARY KEY, name TEXT NOT NULL);",
+    ///             Some("DROP TABLE bakery;"),
+    ///         ))
+    ///     };
+    ///     let cake = || -> Box<dyn MigratorTrait> {
+    ///         Box::new(SqlMigration::new(
+    ///             "M20210102020202CreateCake",
+    ///             "CREATE TABLE cake (id INTEGER PRIMARY KEY, name TEXT NOT NULL);",
+    ///             Some("DROP TABLE cake;"),
+    ///         ))
+    ///     };
+    ///     Migrator::run(&db, &mut vec![bakery()]).await?;
+    ///     db.execute(Statement::from_string(
+    ///         db.get_database_backend(),
+    ///         "INSERT INTO bakery (name) VALUES ('Sponge Bakery')".into(),
+    ///     ))
+    ///     .await?;
+    ///
+    ///     // The bakery table is dropped and created again but the pending cake migration is not run.
+    ///     let redone = Migrator::redo(&db, &[bakery(), cake()]).await?;
+    ///     assert_eq!(redone.as_deref(), Some("M20210101020202CreateBakery"));
+    ///
+    ///     let bakeries = db
+    ///         .query_all(Statement::from_string(
+    ///             db.get_database_backend(),
+    ///             "SELECT * FROM bakery".into(),
+    ///         ))
+    ///         .await?;
+    ///     assert!(bakeries.is_empty());
+    ///     let cake_tables = db
+    ///         .query_all(Statement::from_string(
+    ///             db.get_database_backend(),
+    ///             "SELECT name FROM sqlite_master WHERE name = 'cake'".into(),
+    ///         ))
+    ///         .await?;
+    ///     assert!(cake_tables.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn redo(
+        db: &DbConn,
+        migrations: &[Box<dyn MigratorTrait>],
+    ) -> Result<Option<String>, MigrationError> {
+        Self::redo_with_options(db, migrations, MigratorOptions::default()).await
+    }
+
+    /// redo_with_options will undo the last migration that was applied to the database and then apply it again using the provided options. Refer to `redo` for more information.
+    pub async fn redo_with_options(
+        db: &DbConn,
+        migrations: &[Box<dyn MigratorTrait>],
+        options: MigratorOptions,
+    ) -> Result<Option<String>, MigrationError> {
+        let mg = MigrationManager::with_options(db, &options);
+        migrations_table::init(db).await?;
+        let lock = migrations_table::lock(db, options.lock_timeout).await?;
+        let result = Self::do_redo(&mg, &lock, migrations, &options).await;
+        let unlock_result = lock.release().await;
+        let redone = result?;
+        unlock_result?;
+        Ok(redone)
+    }
+
+    /// status will return every migration along with whether it has been applied to the database. Migrations which have been applied but were not provided are returned as `MigrationState::Missing`.
+    ///
+    /// ```rust
+    /// use sea_migrations::{Migrator, MigrationState};
+    /// use sea_orm::{ Database, DbErr };
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///
+    ///     let pending = Migrator::status(&db, &[])
+    ///         .await?
+    ///         .into_iter()
+    ///         .filter(|migration| migration.state == MigrationState::Pending)
+    ///         .count();
+    ///     assert_eq!(pending, 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn status(
+        db: &DbConn,
+        migrations: &[Box<dyn MigratorTrait>],
+    ) -> Result<Vec<MigrationStatus>, DbErr> {
+        migrations_table::init(db).await?;
+        let applied = migrations_table::get_applied_versions(db).await?;
+
+        let mut status = migrations
+            .iter()
+            .map(|migration| MigrationStatus {
+                name: migration.name().to_string(),
+                state: if applied.iter().any(|version| version == migration.name()) {
+                    MigrationState::Applied
+                } else {
+                    MigrationState::Pending
+                },
+            })
+            .collect::<Vec<_>>();
+        for version in applied {
+            if !migrations
+                .iter()
+                .any(|migration| migration.name() == version)
+            {
+                status.push(MigrationStatus {
+                    name: version,
+                    state: MigrationState::Missing,
+                });
+            }
+        }
+        status.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(status)
+    }
+
+    /// fresh will drop every table, view, enum type and function in the database and then run all of the migrations from the beginning. The `down` functions of the migrations are not used.
+    /// This will delete all of your data so it should only be used in development!
+    pub async fn fresh(
+        db: &DbConn,
+        migrations: &mut Vec<Box<dyn MigratorTrait>>,
+    ) -> Result<(), MigrationError> {
+        Self::fresh_with_options(db, migrations, MigratorOptions::default()).await
+    }
+
+    /// fresh_with_options will drop everything in the database and then run all of the migrations from the beginning using the provided options. Refer to `fresh` for more information.
+    pub async fn fresh_with_options(
+        db: &DbConn,
+        migrations: &mut Vec<Box<dyn MigratorTrait>>,
+        options: MigratorOptions,
+    ) -> Result<(), MigrationError> {
+        let mg = MigrationManager::with_options(db, &options);
+        migrations_table::init(db).await?;
+        let lock = migrations_table::lock(db, options.lock_timeout).await?;
+        let result = Self::do_fresh(&mg, &lock, migrations, &options).await;
+        let unlock_result = lock.release().await;
+        result?;
+        unlock_result?;
+        Ok(())
+    }
+
+    /// force_unlock will remove the lock on the migrations table. The lock is normally removed automatically but it can be left behind if the process running the migrations is killed.
+    /// Only use this if you are sure no other process is currently running migrations!
+    ///
+    /// ```rust
+    /// use sea_migrations::Migrator;
+    /// use sea_orm::{ Database, DbErr };
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DbErr> {
+    ///     let db = Database::connect("sqlite::memory:").await?;
+    ///
+    ///     Migrator::force_unlock(&db).await
+    /// }
+    /// ```
+    pub async fn force_unlock(db: &DbConn) -> Result<(), DbErr> {
         migrations_table::init(db).await?;
-        migrations_table::lock(db).await?;
-        let result = Self::do_migrations(&mg, migrations).await;
-        migrations_table::unlock(db).await?;
-        result
+        migrations_table::unlock(db).await
+    }
+
+    // undo_migrations reverts the last `steps` applied migrations. This function exists so it is easier to capture the error in the `down` function.
+    async fn undo_migrations<'a>(
+        mg: &'a MigrationManager<'a>,
+        lock: &LockGuard<'_>,
+        migrations: &[Box<dyn MigratorTrait>],
+        steps: usize,
+    ) -> Result<(), MigrationError> {
+        let applied = migrations_table::get_applied_versions(mg.db).await?;
+
+        for version in applied.iter().rev().take(steps) {
+            let migration = Self::find_applied(migrations, version)?;
+            Self::revert_migration(mg, lock, migration).await?;
+        }
+
+        Ok(())
+    }
+
+    // do_redo reverts the last applied migration and then applies it again. This function exists so it is easier to capture the error in the `redo` function.
+    async fn do_redo<'a>(
+        mg: &'a MigrationManager<'a>,
+        lock: &LockGuard<'_>,
+        migrations: &[Box<dyn MigratorTrait>],
+        options: &MigratorOptions,
+    ) -> Result<Option<String>, MigrationError> {
+        let version = match migrations_table::get_applied_versions(mg.db).await?.pop() {
+            Some(version) => version,
+            None => return Ok(None),
+        };
+
+        let migration = Self::find_applied(migrations, &version)?;
+        Self::revert_migration(mg, lock, migration).await?;
+        Self::apply_migration(mg, lock, migration, options).await?;
+        Ok(Some(version))
+    }
+
+    // find_applied finds the provided migration for a version in the migrations table. An error is returned if it wasn't provided as it can't be undone.
+    fn find_applied<'m>(
+        migrations: &'m [Box<dyn MigratorTrait>],
+        version: &str,
+    ) -> Result<&'m dyn MigratorTrait, DbErr> {
+        migrations
+            .iter()
+            .find(|migration| migration.name() == version)
+            .map(|migration| migration.as_ref())
+            .ok_or_else(|| {
+                DbErr::Custom(format!(
+                    "The applied migration '{}' was not provided so it can't be undone!",
+                    version
+                ))
+            })
+    }
+
+    // revert_migration runs the `down` function of an applied migration and removes it from the migrations table.
+    async fn revert_migration<'a>(
+        mg: &'a MigrationManager<'a>,
+        lock: &LockGuard<'_>,
+        migration: &dyn MigratorTrait,
+    ) -> Result<(), MigrationError> {
+        lock.refresh().await?;
+        if let Err(error) = catch_unwind(migration.down(mg)).await {
+            return Err(MigrationError::Revert {
+                migration: migration.name().to_string(),
+                error,
+            });
+        }
+        migrations_table::remove_migration(mg.db, migration.name()).await?;
+        Ok(())
+    }
+
+    // do_fresh empties the database and then runs the Database migrations. This function exists so it is easier to capture the error in the `fresh` function.
+    async fn do_fresh<'a>(
+        mg: &'a MigrationManager<'a>,
+        lock: &LockGuard<'_>,
+        migrations: &mut Vec<Box<dyn MigratorTrait>>,
+        options: &MigratorOptions,
+    ) -> Result<(), MigrationError> {
+        drop_all::drop_all(mg.db).await?;
+        for version in migrations_table::get_applied_versions(mg.db).await? {
+            migrations_table::remove_migration(mg.db, &version).await?;
+        }
+
+        Self::do_migrations(mg, lock, migrations, options).await
     }
 
     // do_migrations runs the Database migrations. This function exists so it is easier to capture the error in the `run` function.
     async fn do_migrations<'a>(
         mg: &'a MigrationManager<'a>,
+        lock: &LockGuard<'_>,
         migrations: &mut Vec<Box<dyn MigratorTrait>>,
-    ) -> Result<(), DbErr> {
+        options: &MigratorOptions,
+    ) -> Result<(), MigrationError> {
         // Sort migrations into predictable order
         migrations.sort_by(|a, b| a.name().cmp(b.name()));
 
         for migration in migrations.iter() {
-            let migration_name = migration.name().to_string();
-            let migration_entry = migrations_table::get_version(mg.db, &migration_name).await?;
-
-            match migration_entry {
-                Some(_) => {}
-                None => {
-                    let result = migration.up(mg).await;
-                    match result {
-                        Ok(_) => {
-                            migrations_table::insert_migration(mg.db, &migration_name).await?;
-                        }
-                        Err(err) => {
-                            migration.down(mg).await?;
-                            return Err(err);
-                        }
-                    }
-                }
+            if migrations_table::get_version(mg.db, &migration.name().to_string())
+                .await?
+                .is_none()
+            {
+                Self::apply_migration(mg, lock, migration.as_ref(), options).await?;
             }
         }
 
         Ok(())
     }
+
+    // apply_migration runs the `up` function of a pending migration and records it in the migrations table. If it fails the `down` function is run depending on the rollback policy.
+    async fn apply_migration<'a>(
+        mg: &'a MigrationManager<'a>,
+        lock: &LockGuard<'_>,
+        migration: &dyn MigratorTrait,
+        options: &MigratorOptions,
+    ) -> Result<(), MigrationError> {
+        let migration_name = migration.name().to_string();
+        lock.refresh().await?;
+        if let Err(up_error) = catch_unwind(migration.up(mg)).await {
+            if options.rollback_policy == RollbackPolicy::RunDown {
+                if let Err(down_error) = catch_unwind(migration.down(mg)).await {
+                    return Err(MigrationError::Down {
+                        migration: migration_name,
+                        up_error,
+                        down_error,
+                    });
+                }
+            }
+
+            return Err(MigrationError::Up {
+                migration: migration_name,
+                error: up_error,
+            });
+        }
+        migrations_table::insert_migration(mg.db, &migration_name).await?;
+        Ok(())
+    }
 }
@@ -1,10 +1,18 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
 use sea_orm::{
-    sea_query::{Alias, ColumnDef, Expr, Query, Table},
-    ConnectionTrait, DbConn, DbErr, QueryResult, Value,
+    sea_query::{Alias, ColumnDef, Expr, Order, Query, SimpleExpr, Table},
+    ConnectionTrait, DatabaseConnection, DbBackend, DbConn, DbErr, QueryResult, Value,
 };
 
 // MIGRATIONS_TABLE_NAME is the name of the table created in the Database to keep track of the current state of the migrations.
-const MIGRATIONS_TABLE_NAME: &str = "_sea_migrations";
+pub(crate) const MIGRATIONS_TABLE_NAME: &str = "_sea_migrations";
 
 // MIGRATIONS_TABLE_VERSION_COLUMN is the name of the column used to store the version of the migrations within the table used to track to current state of migrations.
 const MIGRATIONS_TABLE_VERSION_COLUMN: &str = "version";
@@ -12,6 +20,106 @@ const MIGRATIONS_TABLE_VERSION_COLUMN: &str = "version";
 // MIGRATIONS_TABLE_LOCK_ROW_VERSION is the version contained in the row that is used to lock the table. If it exists then the table is locked and migrations are in progress. This should prevent any other process from running migrations at the same time.
 const MIGRATIONS_TABLE_LOCK_ROW_VERSION: &str = "_lock";
 
+// MIGRATIONS_TABLE_OWNER_COLUMN is the name of the column which stores the token of the lock row. It is NULL for every other row.
+const MIGRATIONS_TABLE_OWNER_COLUMN: &str = "owner";
+
+// MIGRATIONS_TABLE_LOCKED_AT_COLUMN is the name of the column which stores when the lock row was last refreshed as a unix timestamp in seconds. It is NULL for every other row.
+const MIGRATIONS_TABLE_LOCKED_AT_COLUMN: &str = "locked_at";
+
+/// LockGuard is returned by `lock` and represents holding the lock on the migrations table.
+/// It must be released with `LockGuard::release`. If it is dropped without being released (eg. because the future running the migrations was cancelled) the lock row is left in the database until it expires (see `lock`).
+pub struct LockGuard<'a> {
+    db: &'a DbConn,
+    token: String,
+}
+
+impl<'a> LockGuard<'a> {
+    /// refresh will update the time the lock was taken so it doesn't expire while the migrations are still running. An error is returned if the lock has been removed or taken over by another process in the meantime.
+    pub async fn refresh(&self) -> Result<(), DbErr> {
+        let backend = self.db.get_database_backend();
+        let stmt = Query::update()
+            .table(Alias::new(MIGRATIONS_TABLE_NAME))
+            .value_expr(
+                Alias::new(MIGRATIONS_TABLE_LOCKED_AT_COLUMN),
+                Expr::cust(now(backend)),
+            )
+            .and_where(lock_row())
+            .and_where(
+                Expr::col(Alias::new(MIGRATIONS_TABLE_OWNER_COLUMN))
+                    .eq(Value::String(Some(Box::new(self.token.clone())))),
+            )
+            .to_owned();
+
+        let result = self.db.execute(backend.build(&stmt)).await?;
+        if result.rows_affected() == 0 {
+            return Err(DbErr::Custom(
+                "The lock on the migrations table was lost! It was removed using `Migrator::force_unlock` or it expired and was taken over by another process.".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// release will unlock the migrations table. Only the lock row created by this guard is removed so a lock which has since been taken over by another process is left alone.
+    pub async fn release(self) -> Result<(), DbErr> {
+        let stmt = Query::delete()
+            .from_table(Alias::new(MIGRATIONS_TABLE_NAME))
+            .and_where(lock_row())
+            .and_where(
+                Expr::col(Alias::new(MIGRATIONS_TABLE_OWNER_COLUMN))
+                    .eq(Value::String(Some(Box::new(self.token)))),
+            )
+            .to_owned();
+
+        self.db
+            .execute(self.db.get_database_backend().build(&stmt))
+            .await?;
+        Ok(())
+    }
+}
+
+// MOCK_LOCK_TOKEN is the token used to take the lock on a mock database connection. A fixed token makes the statements logged by the mock database the same every time the migrations are run.
+const MOCK_LOCK_TOKEN: &str = "mock";
+
+// lock_token returns a new random token identifying a single acquisition of the lock. The token is unique even when the lock is taken multiple times by the same process.
+fn lock_token(db: &DbConn) -> String {
+    if let DatabaseConnection::MockDatabaseConnection(_) = db {
+        return MOCK_LOCK_TOKEN.to_string();
+    }
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut token = String::new();
+    // Every RandomState is seeded with random keys so hashing the same input with two of them gives two independent random values.
+    for _ in 0..2 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.write_u32(process::id());
+        hasher.write_u64(count);
+        token += &format!("{:016x}", hasher.finish());
+    }
+    token
+}
+
+// now returns the SQL for the current unix timestamp in seconds. The time of the database is used instead of the time of this process so the expiry of the lock doesn't depend on the clocks of the machines running migrations being in sync.
+fn now(backend: DbBackend) -> &'static str {
+    match backend {
+        DbBackend::MySql => "UNIX_TIMESTAMP()",
+        DbBackend::Postgres => "CAST(EXTRACT(EPOCH FROM NOW()) AS BIGINT)",
+        DbBackend::Sqlite => "CAST(strftime('%s', 'now') AS INTEGER)",
+    }
+}
+
+// lock_row returns the condition matching the lock row of the migrations table.
+fn lock_row() -> SimpleExpr {
+    Expr::col(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN)).eq(Value::String(Some(Box::new(
+        MIGRATIONS_TABLE_LOCK_ROW_VERSION.to_string(),
+    ))))
+}
+
 /// init will create the migrations table in the database if it does not exist.
 pub async fn init(db: &DbConn) -> Result<(), DbErr> {
     let stmt = Table::create()
@@ -23,54 +131,105 @@ pub async fn init(db: &DbConn) -> Result<(), DbErr> {
                 .not_null()
                 .primary_key(),
         )
+        .col(ColumnDef::new(Alias::new(MIGRATIONS_TABLE_OWNER_COLUMN)).string())
+        .col(ColumnDef::new(Alias::new(MIGRATIONS_TABLE_LOCKED_AT_COLUMN)).big_integer())
         .to_owned();
 
     db.execute(db.get_database_backend().build(&stmt)).await?;
-    Ok(())
+
+    // Migrations tables created by older versions of sea_migrations don't have the lock columns.
+    add_column_if_missing(
+        db,
+        ColumnDef::new(Alias::new(MIGRATIONS_TABLE_OWNER_COLUMN)).string(),
+    )
+    .await?;
+    add_column_if_missing(
+        db,
+        ColumnDef::new(Alias::new(MIGRATIONS_TABLE_LOCKED_AT_COLUMN)).big_integer(),
+    )
+    .await
+}
+
+// add_column_if_missing adds a column to the migrations table. Not every database supports `ADD COLUMN IF NOT EXISTS` so the error returned when the column already exists is ignored instead.
+async fn add_column_if_missing(db: &DbConn, column: &mut ColumnDef) -> Result<(), DbErr> {
+    let stmt = Table::alter()
+        .table(Alias::new(MIGRATIONS_TABLE_NAME))
+        .add_column(column)
+        .to_owned();
+
+    match db.execute(db.get_database_backend().build(&stmt)).await {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            let message = err.to_string().to_lowercase();
+            // SQLite and MySQL report a "duplicate column" while Postgres reports that the column "already exists".
+            if message.contains("duplicate column") || message.contains("already exists") {
+                Ok(())
+            } else {
+                Err(err)
+            }
+        }
+    }
 }
 
 /// lock will mark the migrations table as locked. This should prevent any other process from running migrations at the same time.
-pub async fn lock(db: &DbConn) -> Result<(), DbErr> {
-    // Check table lock
-    let stmt = Query::select()
-        .column(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN))
+/// A lock which hasn't been refreshed (see `LockGuard::refresh`) within the timeout is assumed to have been left behind by a process which was cancelled or killed and is taken over.
+pub async fn lock(db: &DbConn, timeout: Duration) -> Result<LockGuard<'_>, DbErr> {
+    let backend = db.get_database_backend();
+
+    // Remove an expired lock
+    let stmt = Query::delete()
+        .from_table(Alias::new(MIGRATIONS_TABLE_NAME))
+        .and_where(lock_row())
         .and_where(
-            Expr::col(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN)).eq(Value::String(Some(
-                Box::new(MIGRATIONS_TABLE_LOCK_ROW_VERSION.to_string()),
-            ))),
+            Expr::col(Alias::new(MIGRATIONS_TABLE_LOCKED_AT_COLUMN)).less_than(Expr::cust(
+                &format!("{} - {}", now(backend), timeout.as_secs()),
+            )),
         )
-        .from(Alias::new(MIGRATIONS_TABLE_NAME))
         .to_owned();
+    db.execute(backend.build(&stmt)).await?;
 
-    let result = db.query_one(db.get_database_backend().build(&stmt)).await?;
-    if result.is_some() {
-        return Err(DbErr::Custom(
-            "Migrations table is locked! Please try again later!".into(),
-        ));
-    }
-
-    // Create table lock
+    // Create table lock. The version column is the primary key so only one process can insert the lock row.
+    let token = lock_token(db);
     let stmt = Query::insert()
         .into_table(Alias::new(MIGRATIONS_TABLE_NAME))
-        .columns(vec![Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN)])
-        .values_panic(vec![Value::String(Some(Box::new(
-            MIGRATIONS_TABLE_LOCK_ROW_VERSION.to_string(),
-        )))])
+        .columns(vec![
+            Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN),
+            Alias::new(MIGRATIONS_TABLE_OWNER_COLUMN),
+            Alias::new(MIGRATIONS_TABLE_LOCKED_AT_COLUMN),
+        ])
+        .exprs_panic(vec![
+            SimpleExpr::Value(Value::String(Some(Box::new(
+                MIGRATIONS_TABLE_LOCK_ROW_VERSION.to_string(),
+            )))),
+            SimpleExpr::Value(Value::String(Some(Box::new(token.clone())))),
+            Expr::cust(now(backend)),
+        ])
         .to_owned();
 
-    db.execute(db.get_database_backend().build(&stmt)).await?;
-    Ok(())
+    if let Err(err) = db.execute(backend.build(&stmt)).await {
+        // Check table lock
+        let stmt = Query::select()
+            .column(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN))
+            .and_where(lock_row())
+            .from(Alias::new(MIGRATIONS_TABLE_NAME))
+            .to_owned();
+
+        if db.query_one(backend.build(&stmt)).await?.is_some() {
+            return Err(DbErr::Custom(
+                "Migrations table is locked! Please try again later! If no other process is running migrations the lock can be removed using `Migrator::force_unlock`.".into(),
+            ));
+        }
+        return Err(err);
+    }
+
+    Ok(LockGuard { db, token })
 }
 
-/// unlock will unmark the migrations table as locked. This will allow any other process to run migrations.
+/// unlock will unmark the migrations table as locked regardless of which process holds the lock. This will allow any other process to run migrations.
 pub async fn unlock(db: &DbConn) -> Result<(), DbErr> {
     let stmt = Query::delete()
         .from_table(Alias::new(MIGRATIONS_TABLE_NAME))
-        .and_where(
-            Expr::col(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN)).eq(Value::String(Some(
-                Box::new(MIGRATIONS_TABLE_LOCK_ROW_VERSION.to_string()),
-            ))),
-        )
+        .and_where(lock_row())
         .to_owned();
 
     db.execute(db.get_database_backend().build(&stmt)).await?;
@@ -102,3 +261,37 @@ pub async fn insert_migration(db: &DbConn, version: &String) -> Result<u32, DbEr
     let result = db.execute(db.get_database_backend().build(&stmt)).await?;
     Ok(result.last_insert_id() as u32)
 }
+
+/// get_applied_versions will return the names of every migration which has been applied to the database in order.
+pub async fn get_applied_versions(db: &DbConn) -> Result<Vec<String>, DbErr> {
+    let stmt = Query::select()
+        .column(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN))
+        .and_where(
+            Expr::col(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN)).ne(Value::String(Some(
+                Box::new(MIGRATIONS_TABLE_LOCK_ROW_VERSION.to_string()),
+            ))),
+        )
+        .from(Alias::new(MIGRATIONS_TABLE_NAME))
+        .order_by(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN), Order::Asc)
+        .to_owned();
+
+    db.query_all(db.get_database_backend().build(&stmt))
+        .await?
+        .iter()
+        .map(|row| row.try_get("", MIGRATIONS_TABLE_VERSION_COLUMN))
+        .collect()
+}
+
+/// remove_migration will delete the migration event with a given name from the database so the migration is no longer considered applied.
+pub async fn remove_migration(db: &DbConn, version: &str) -> Result<(), DbErr> {
+    let stmt = Query::delete()
+        .from_table(Alias::new(MIGRATIONS_TABLE_NAME))
+        .and_where(
+            Expr::col(Alias::new(MIGRATIONS_TABLE_VERSION_COLUMN))
+                .eq(Value::String(Some(Box::new(version.to_string())))),
+        )
+        .to_owned();
+
+    db.execute(db.get_database_backend().build(&stmt)).await?;
+    Ok(())
+}
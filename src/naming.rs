@@ -0,0 +1,52 @@
+use std::fmt::Debug;
+
+/// NamingConvention is used to name the constraints and indexes created by the MigrationManager. Using predictable names means they can be referenced later on (eg. to drop them) and are the same on every database backend.
+/// Every method has a default implementation so you only need to override the names you want to change.
+///
+/// ```rust
+/// use sea_migrations::{DefaultNamingConvention, NamingConvention};
+///
+/// #[derive(Debug)]
+/// pub struct MyNamingConvention;
+///
+/// impl NamingConvention for MyNamingConvention {
+///     fn index(&self, table: &str, columns: &[&str]) -> String {
+///         format!("{}_{}_index", table, columns.join("_"))
+///     }
+/// }
+///
+/// assert_eq!(MyNamingConvention.index("cake", &["name"]), "cake_name_index");
+/// assert_eq!(DefaultNamingConvention.index("cake", &["name"]), "idx_cake_name");
+/// ```
+pub trait NamingConvention: Debug + Send + Sync {
+    /// foreign_key returns the name of the foreign key constraint from `columns` on `table` to `ref_table`. Defaults to `fk_<table>_<columns>_<ref_table>`.
+    fn foreign_key(&self, table: &str, columns: &[&str], ref_table: &str) -> String {
+        format!("fk_{}_{}_{}", table, columns.join("_"), ref_table)
+    }
+
+    /// index returns the name of the index on `columns` of `table`. Defaults to `idx_<table>_<columns>`.
+    fn index(&self, table: &str, columns: &[&str]) -> String {
+        format!("idx_{}_{}", table, columns.join("_"))
+    }
+
+    /// unique returns the name of the unique constraint on `columns` of `table`. Defaults to `uq_<table>_<columns>`.
+    fn unique(&self, table: &str, columns: &[&str]) -> String {
+        format!("uq_{}_{}", table, columns.join("_"))
+    }
+
+    /// check returns the name of the check constraint on `columns` of `table`. Defaults to `ck_<table>_<columns>`.
+    fn check(&self, table: &str, columns: &[&str]) -> String {
+        format!("ck_{}_{}", table, columns.join("_"))
+    }
+
+    /// trigger returns the name of the trigger maintaining `columns` of `table` (eg. the trigger created by `add_updated_at_trigger`). Defaults to `trg_<table>_<columns>`.
+    fn trigger(&self, table: &str, columns: &[&str]) -> String {
+        format!("trg_{}_{}", table, columns.join("_"))
+    }
+}
+
+/// DefaultNamingConvention is the NamingConvention used by sea_migrations unless another one is provided.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultNamingConvention;
+
+impl NamingConvention for DefaultNamingConvention {}
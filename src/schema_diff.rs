@@ -0,0 +1,503 @@
+use std::fmt;
+
+use sea_orm::{
+    ConnectionTrait, DbBackend, DbConn, DbErr, EntityTrait, Iden, IdenStatic, Iterable,
+    RelationTrait,
+};
+
+use crate::{
+    introspect_table,
+    seaorm_integration::{
+        get_expected_table, identity_to_column_names, table_ref_to_alias, SchemaContext,
+    },
+    IntrospectedColumn, IntrospectedTable, MigratorOptions,
+};
+
+/// SchemaDifference is a difference between the schema expected by a SeaORM Entity and the schema of a live database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaDifference {
+    /// MissingTable is returned when the table of an Entity doesn't exist.
+    MissingTable {
+        /// table is the name of the table.
+        table: String,
+    },
+    /// MissingColumn is returned when a column of an Entity doesn't exist in the table.
+    MissingColumn {
+        /// table is the name of the table.
+        table: String,
+        /// column is the name of the column.
+        column: String,
+    },
+    /// ExtraColumn is returned when the table has a column which isn't part of the Entity.
+    ExtraColumn {
+        /// table is the name of the table.
+        table: String,
+        /// column is the name of the column.
+        column: String,
+    },
+    /// ColumnType is returned when the type of a column doesn't match the type of the Entity's column.
+    ColumnType {
+        /// table is the name of the table.
+        table: String,
+        /// column is the name of the column.
+        column: String,
+        /// expected is the type of the column in the Entity for the database backend.
+        expected: String,
+        /// actual is the type of the column reported by the database.
+        actual: String,
+    },
+    /// Nullability is returned when a column is nullable in the database but not in the Entity or the other way around.
+    Nullability {
+        /// table is the name of the table.
+        table: String,
+        /// column is the name of the column.
+        column: String,
+        /// expected_nullable is whether the Entity's column is nullable.
+        expected_nullable: bool,
+    },
+    /// MissingForeignKey is returned when a relation of an Entity doesn't have a matching foreign key in the table.
+    MissingForeignKey {
+        /// table is the name of the table.
+        table: String,
+        /// columns are the columns which should reference the other table.
+        columns: Vec<String>,
+        /// referenced_table is the name of the table which should be referenced.
+        referenced_table: String,
+        /// referenced_columns are the columns which should be referenced.
+        referenced_columns: Vec<String>,
+    },
+    /// ExtraForeignKey is returned when the table has a foreign key which doesn't match a relation of the Entity.
+    ExtraForeignKey {
+        /// table is the name of the table.
+        table: String,
+        /// name is the name of the foreign key constraint. It is None on SQLite which doesn't report the names of foreign keys.
+        name: Option<String>,
+        /// columns are the columns which reference the other table.
+        columns: Vec<String>,
+        /// referenced_table is the name of the table which is referenced.
+        referenced_table: String,
+        /// referenced_columns are the columns which are referenced.
+        referenced_columns: Vec<String>,
+    },
+    /// MissingIndex is returned when a unique or indexed column of an Entity doesn't have a matching index in the table.
+    MissingIndex {
+        /// table is the name of the table.
+        table: String,
+        /// columns are the columns which should be indexed.
+        columns: Vec<String>,
+        /// unique is whether the index should be unique.
+        unique: bool,
+    },
+}
+
+impl fmt::Display for SchemaDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTable { table } => write!(f, "Table '{}' is missing", table),
+            Self::MissingColumn { table, column } => {
+                write!(f, "Column '{}.{}' is missing", table, column)
+            }
+            Self::ExtraColumn { table, column } => {
+                write!(f, "Column '{}.{}' is not part of the entity", table, column)
+            }
+            Self::ColumnType {
+                table,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Column '{}.{}' has type '{}' but the entity expects '{}'",
+                table, column, actual, expected
+            ),
+            Self::Nullability {
+                table,
+                column,
+                expected_nullable,
+            } => write!(
+                f,
+                "Column '{}.{}' should {}be nullable",
+                table,
+                column,
+                if *expected_nullable { "" } else { "not " }
+            ),
+            Self::MissingForeignKey {
+                table,
+                columns,
+                referenced_table,
+                referenced_columns,
+            } => write!(
+                f,
+                "Foreign key from '{}' ({}) to '{}' ({}) is missing",
+                table,
+                columns.join(", "),
+                referenced_table,
+                referenced_columns.join(", ")
+            ),
+            Self::ExtraForeignKey {
+                table,
+                columns,
+                referenced_table,
+                referenced_columns,
+                ..
+            } => write!(
+                f,
+                "Foreign key from '{}' ({}) to '{}' ({}) is not part of the entity",
+                table,
+                columns.join(", "),
+                referenced_table,
+                referenced_columns.join(", ")
+            ),
+            Self::MissingIndex {
+                table,
+                columns,
+                unique,
+            } => write!(
+                f,
+                "{} on '{}' ({}) is missing",
+                if *unique { "Unique index" } else { "Index" },
+                table,
+                columns.join(", ")
+            ),
+        }
+    }
+}
+
+// ExpectedTable converts an Entity into the table it expects for a database backend. It is boxed so Entities of different types can be stored together.
+type ExpectedTable = Box<dyn Fn(&SchemaContext) -> IntrospectedTable + Send + Sync>;
+
+// ExpectedEntity is an Entity added to a SchemaDiff.
+pub(crate) struct ExpectedEntity {
+    pub table: ExpectedTable,
+    pub paths: EntityPaths,
+}
+
+// EntityPaths holds the Rust paths of an Entity and it's Columns and Relations so code using them can be generated.
+pub(crate) struct EntityPaths {
+    // entity is the path of the Entity type (eg. `crate::models::customer::Entity`).
+    pub entity: String,
+    // columns maps the name of each column to the path of it's Column variant.
+    pub columns: Vec<(String, String)>,
+    // relations maps the (columns, referenced table, referenced columns) of each foreign key to the path of it's Relation variant.
+    pub relations: Vec<(ForeignKeyColumns, String)>,
+}
+
+// ForeignKeyColumns is the (columns, referenced table, referenced columns) of a foreign key.
+pub(crate) type ForeignKeyColumns = (Vec<String>, String, Vec<String>);
+
+impl EntityPaths {
+    // new finds the paths of an Entity using `std::any::type_name`. The crate name at the start of each path is replaced with `crate` so the code works inside of the crate which defines the Entity.
+    fn new<E: EntityTrait>() -> Self {
+        let column_type = rust_path(std::any::type_name::<E::Column>());
+        let relation_type = rust_path(std::any::type_name::<E::Relation>());
+        Self {
+            entity: rust_path(std::any::type_name::<E>()),
+            columns: E::Column::iter()
+                .map(|column| {
+                    (
+                        column.as_str().to_string(),
+                        format!("{}::{:?}", column_type, column),
+                    )
+                })
+                .collect(),
+            relations: E::Relation::iter()
+                .filter(|relation| !relation.def().is_owner)
+                .map(|relation| {
+                    let rel_def = relation.def();
+                    (
+                        (
+                            identity_to_column_names(&rel_def.from_col),
+                            table_ref_to_alias(rel_def.to_tbl).to_string(),
+                            identity_to_column_names(&rel_def.to_col),
+                        ),
+                        format!("{}::{:?}", relation_type, relation),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+// rust_path replaces the name of the crate at the start of a path returned by `std::any::type_name` with `crate`.
+fn rust_path(type_name: &str) -> String {
+    match type_name.split_once("::") {
+        Some((_, path)) => format!("crate::{}", path),
+        None => type_name.to_string(),
+    }
+}
+
+/// SchemaDiff compares the schema of a live database against a set of SeaORM Entities. This can be used in CI to check that your migrations create the schema your Entities expect.
+/// Column types are compared using the types `create_table` would create the columns with. Indexes which exist in the database but not in the Entities are not reported as they are often created by hand.
+///
+/// ```rust
+/// use sea_migrations::{MigrationManager, SchemaDiff, SchemaDifference};
+/// use sea_orm::{entity::prelude::*, Database};
+///
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+/// #[sea_orm(table_name = "cake")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     pub name: String,
+///     #[sea_orm(nullable)]
+///     pub description: Option<String>,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+/// pub enum Relation {}
+///
+/// impl ActiveModelBehavior for ActiveModel {}
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DbErr> {
+///     let db = Database::connect("sqlite::memory:").await?;
+///     let diff = SchemaDiff::new().entity(Entity);
+///
+///     assert_eq!(
+///         diff.diff(&db).await?,
+///         vec![SchemaDifference::MissingTable { table: "cake".into() }]
+///     );
+///
+///     MigrationManager::new(&db).create_table(Entity).await?;
+///     assert_eq!(diff.diff(&db).await?, vec![]);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct SchemaDiff {
+    pub(crate) entities: Vec<ExpectedEntity>,
+}
+
+impl SchemaDiff {
+    /// new creates a SchemaDiff without any Entities. Add Entities using the `entity` method.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// entity adds a SeaORM Entity to be compared with the database.
+    pub fn entity<E: 'static>(mut self, _entity: E) -> Self
+    where
+        E: EntityTrait,
+    {
+        self.entities.push(ExpectedEntity {
+            table: Box::new(get_expected_table::<E>),
+            paths: EntityPaths::new::<E>(),
+        });
+        self
+    }
+
+    /// is_empty returns whether no Entities have been added.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// diff will compare the Entities with the database and return every difference. An empty list means the database matches the Entities.
+    pub async fn diff(&self, db: &DbConn) -> Result<Vec<SchemaDifference>, DbErr> {
+        self.diff_with_options(db, &MigratorOptions::default())
+            .await
+    }
+
+    /// diff_with_options will compare the Entities with the database using the provided options. Use the same options the migrations are run with so auto incrementing columns are compared correctly.
+    pub async fn diff_with_options(
+        &self,
+        db: &DbConn,
+        options: &MigratorOptions,
+    ) -> Result<Vec<SchemaDifference>, DbErr> {
+        Ok(self
+            .diff_entities(db, options)
+            .await?
+            .into_iter()
+            .flat_map(|(_, _, differences)| differences)
+            .collect())
+    }
+
+    // diff_entities compares each Entity with the database and returns the table each one expects along with the differences.
+    pub(crate) async fn diff_entities(
+        &self,
+        db: &DbConn,
+        options: &MigratorOptions,
+    ) -> Result<Vec<(&ExpectedEntity, IntrospectedTable, Vec<SchemaDifference>)>, DbErr> {
+        let backend = db.get_database_backend();
+        let ctx = SchemaContext {
+            backend,
+            naming: options.naming_convention.as_ref(),
+            postgres_auto_increment: options.postgres_auto_increment,
+        };
+
+        let mut results = Vec::new();
+        for entity in &self.entities {
+            let expected = (entity.table)(&ctx);
+            let mut differences = Vec::new();
+            match introspect_table(db, &expected.name).await? {
+                Some(actual) => diff_table(backend, &expected, &actual, &mut differences),
+                None => differences.push(SchemaDifference::MissingTable {
+                    table: expected.name.clone(),
+                }),
+            }
+            results.push((entity, expected, differences));
+        }
+
+        Ok(results)
+    }
+}
+
+// diff_table adds the differences between the table expected by an Entity and the table in the database to `differences`.
+fn diff_table(
+    backend: DbBackend,
+    expected: &IntrospectedTable,
+    actual: &IntrospectedTable,
+    differences: &mut Vec<SchemaDifference>,
+) {
+    let table = || expected.name.clone();
+
+    for expected_column in &expected.columns {
+        match find_column(actual, &expected_column.name) {
+            Some(actual_column) => {
+                if normalize_type(backend, &expected_column.data_type)
+                    != normalize_type(backend, &actual_column.data_type)
+                {
+                    differences.push(SchemaDifference::ColumnType {
+                        table: table(),
+                        column: expected_column.name.clone(),
+                        expected: expected_column.data_type.clone(),
+                        actual: actual_column.data_type.clone(),
+                    });
+                }
+                if expected_column.nullable != actual_column.nullable {
+                    differences.push(SchemaDifference::Nullability {
+                        table: table(),
+                        column: expected_column.name.clone(),
+                        expected_nullable: expected_column.nullable,
+                    });
+                }
+            }
+            None => differences.push(SchemaDifference::MissingColumn {
+                table: table(),
+                column: expected_column.name.clone(),
+            }),
+        }
+    }
+    for actual_column in &actual.columns {
+        if find_column(expected, &actual_column.name).is_none() {
+            differences.push(SchemaDifference::ExtraColumn {
+                table: table(),
+                column: actual_column.name.clone(),
+            });
+        }
+    }
+
+    // Foreign keys and indexes are compared by what they do as their names depend on the naming convention they were created with.
+    for foreign_key in &expected.foreign_keys {
+        let exists = actual.foreign_keys.iter().any(|actual_foreign_key| {
+            actual_foreign_key.columns == foreign_key.columns
+                && actual_foreign_key.referenced_table == foreign_key.referenced_table
+                && actual_foreign_key.referenced_columns == foreign_key.referenced_columns
+        });
+        if !exists {
+            differences.push(SchemaDifference::MissingForeignKey {
+                table: table(),
+                columns: foreign_key.columns.clone(),
+                referenced_table: foreign_key.referenced_table.clone(),
+                referenced_columns: foreign_key.referenced_columns.clone(),
+            });
+        }
+    }
+    for foreign_key in &actual.foreign_keys {
+        let expected = expected.foreign_keys.iter().any(|expected_foreign_key| {
+            expected_foreign_key.columns == foreign_key.columns
+                && expected_foreign_key.referenced_table == foreign_key.referenced_table
+                && expected_foreign_key.referenced_columns == foreign_key.referenced_columns
+        });
+        if !expected {
+            differences.push(SchemaDifference::ExtraForeignKey {
+                table: table(),
+                name: foreign_key.name.clone(),
+                columns: foreign_key.columns.clone(),
+                referenced_table: foreign_key.referenced_table.clone(),
+                referenced_columns: foreign_key.referenced_columns.clone(),
+            });
+        }
+    }
+
+    for index in &expected.indexes {
+        let exists = actual.indexes.iter().any(|actual_index| {
+            actual_index.columns == index.columns && actual_index.unique == index.unique
+        });
+        if !exists {
+            differences.push(SchemaDifference::MissingIndex {
+                table: table(),
+                columns: index.columns.clone(),
+                unique: index.unique,
+            });
+        }
+    }
+}
+
+// find_column finds a column of a table by it's name.
+fn find_column<'a>(table: &'a IntrospectedTable, name: &str) -> Option<&'a IntrospectedColumn> {
+    table.columns.iter().find(|column| column.name == name)
+}
+
+// normalize_type converts the name of a column type into a (type, parameters) pair so the types sea_query creates can be compared with the types reported by the database.
+// The parameters (eg. the length of a varchar) are only compared when both types have them as databases add default parameters to some types (eg. `int(11)` on MySQL).
+fn normalize_type(backend: DbBackend, data_type: &str) -> NormalizedType {
+    let data_type = data_type.to_ascii_lowercase().replace(&['"', '`'][..], "");
+
+    let mut name = String::new();
+    let mut params = String::new();
+    let mut depth = 0;
+    for c in data_type.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if depth > 0 => {
+                if !c.is_whitespace() {
+                    params.push(c);
+                }
+            }
+            c => name.push(c),
+        }
+    }
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    // Types from another schema are qualified by the schema name (eg. `public.mood`).
+    let name = name.rsplit('.').next().unwrap_or_default();
+
+    let name = match (backend, name) {
+        (DbBackend::Postgres, "varchar") => "character varying",
+        (DbBackend::Postgres, "char" | "bpchar") => "character",
+        (DbBackend::Postgres, "int" | "int4") => "integer",
+        (DbBackend::Postgres, "int2") => "smallint",
+        (DbBackend::Postgres, "int8") => "bigint",
+        (DbBackend::Postgres, "float4") => "real",
+        (DbBackend::Postgres, "float8") => "double precision",
+        (DbBackend::Postgres, "decimal") => "numeric",
+        (DbBackend::Postgres, "bool") => "boolean",
+        (DbBackend::Postgres, "timestamp") => "timestamp without time zone",
+        (DbBackend::Postgres, "timestamptz") => "timestamp with time zone",
+        (DbBackend::Postgres, "time") => "time without time zone",
+        (DbBackend::MySql, "integer") => "int",
+        (DbBackend::MySql, "bool" | "boolean") => "tinyint",
+        (DbBackend::MySql, "numeric") => "decimal",
+        (_, name) => name,
+    };
+
+    NormalizedType {
+        name: name.to_string(),
+        params,
+    }
+}
+
+// NormalizedType is the name of a column type and it's parameters as returned by `normalize_type`.
+#[derive(Debug)]
+struct NormalizedType {
+    name: String,
+    params: String,
+}
+
+impl PartialEq for NormalizedType {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && (self.params.is_empty() || other.params.is_empty() || self.params == other.params)
+    }
+}
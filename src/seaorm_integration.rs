@@ -1,77 +1,464 @@
 use sea_orm::{
-    sea_query::{Alias, ColumnDef, ForeignKey, ForeignKeyCreateStatement, TableRef},
-    ColumnTrait, ColumnType, EntityTrait, Iden, PrimaryKeyToColumn, PrimaryKeyTrait, RelationTrait,
-    RelationType,
+    sea_query::{
+        inject_parameters, Alias, ColumnDef, ColumnSpec, ForeignKey, ForeignKeyCreateStatement,
+        Index, IndexCreateStatement, MysqlQueryBuilder, PostgresQueryBuilder, QueryBuilder,
+        QueryStatementBuilder, SchemaStatementBuilder, SimpleExpr, SqlWriter, SqliteQueryBuilder,
+        Table, TableBuilder, TableCreateStatement, TableRef,
+    },
+    ColumnTrait, ColumnType, DbBackend, EntityTrait, Iden, IdenStatic, Identity, Iterable,
+    PrimaryKeyToColumn, PrimaryKeyTrait, RelationDef, RelationTrait, RelationType, Statement,
 };
 
+use crate::{
+    ColumnSchema, ForeignKeySchema, IndexSchema, IntrospectedColumn, IntrospectedForeignKey,
+    IntrospectedIndex, IntrospectedTable, NamingConvention, PostgresAutoIncrement, TableSchema,
+};
+
+// SchemaContext holds the settings which affect how sea_orm Entities are converted into sea_query statements.
+pub(crate) struct SchemaContext<'a> {
+    pub backend: DbBackend,
+    pub naming: &'a dyn NamingConvention,
+    pub postgres_auto_increment: PostgresAutoIncrement,
+}
+
 // CustomColumnDef is a copy of the struct defined at https://github.com/SeaQL/sea-orm/blob/master/src/entity/column.rs#L7 with all fields set to public.
 // It exists so that the unsafe transmutate operation can be applied to access private fields on the struct.
 // This is a TEMPORARY solution and I will ask if these values can be directly exposed by sea_query in the future. This solution relies on internal implementation details of sea_query and unsafe code which is not good!
-struct CustomColumnDef {
+pub(crate) struct CustomColumnDef {
     pub col_type: ColumnType,
     pub null: bool,
     pub unique: bool,
     pub indexed: bool,
 }
 
-// get_column_def is used to convert between the sea_orm Column and the sea_query ColumnDef.
-pub(crate) fn get_column_def<T: EntityTrait>(column: T::Column) -> ColumnDef {
-    let column_def_prelude: CustomColumnDef = unsafe { std::mem::transmute(column.def()) }; // Note: This is used to access private fields and hence relies on internal implementation details of sea_query and unsafe code which is not good!
-    let mut column_def =
-        ColumnDef::new_with_type(column, column_def_prelude.col_type.clone().into());
-    if !column_def_prelude.null {
+// get_column_prelude is used to access the private fields of the sea_orm ColumnDef for a Column.
+pub(crate) fn get_column_prelude<C: ColumnTrait>(column: C) -> CustomColumnDef {
+    unsafe { std::mem::transmute(column.def()) } // Note: This is used to access private fields and hence relies on internal implementation details of sea_query and unsafe code which is not good!
+}
+
+// get_table_schema converts a sea_orm Entity into a TableSchema. Columns and relations for which the filter functions return false are left out of the table.
+pub(crate) fn get_table_schema<E: 'static>(
+    include_column: impl Fn(&E::Column) -> bool,
+    include_relation: impl Fn(&E::Relation) -> bool,
+) -> TableSchema
+where
+    E: EntityTrait,
+{
+    TableSchema {
+        name: E::default().table_name().to_string(),
+        columns: E::Column::iter()
+            .filter(|column| include_column(column))
+            .map(get_column_schema::<E>)
+            .collect(),
+        primary_key: E::PrimaryKey::iter()
+            .map(|key| key.into_column().as_str().to_string())
+            .collect(),
+        foreign_keys: E::Relation::iter()
+            .filter(|relation| include_relation(relation))
+            .map(|relation| relation.def())
+            .filter(|rel_def| !rel_def.is_owner)
+            .map(|rel_def| get_foreign_key_schema(&rel_def))
+            .collect(),
+        indexes: E::Column::iter()
+            .filter(|column| include_column(column))
+            // Primary keys are already unique and indexed.
+            .filter(|column| E::PrimaryKey::from_column(*column).is_none())
+            .filter_map(|column| {
+                let column_def_prelude = get_column_prelude(column);
+                if !column_def_prelude.unique && !column_def_prelude.indexed {
+                    return None;
+                }
+                Some(IndexSchema {
+                    columns: vec![column.as_str().to_string()],
+                    unique: column_def_prelude.unique,
+                })
+            })
+            .collect(),
+    }
+}
+
+// get_column_schema converts a sea_orm Column into a ColumnSchema.
+pub(crate) fn get_column_schema<T: EntityTrait>(column: T::Column) -> ColumnSchema {
+    let column_def_prelude = get_column_prelude(column);
+    ColumnSchema {
+        name: column.as_str().to_string(),
+        col_type: column_def_prelude.col_type,
+        nullable: column_def_prelude.null,
+        // SeaORM Entities can't describe the default value of a column.
+        default: None,
+        auto_increment: is_auto_increment_column::<T>(column),
+    }
+}
+
+// get_foreign_key_schema converts a sea_orm Relation into a ForeignKeySchema.
+pub(crate) fn get_foreign_key_schema(rel_def: &RelationDef) -> ForeignKeySchema {
+    match rel_def.rel_type {
+        RelationType::HasOne => ForeignKeySchema {
+            columns: identity_to_column_names(&rel_def.from_col),
+            referenced_table: table_ref_to_alias(rel_def.to_tbl.clone()).to_string(),
+            referenced_columns: identity_to_column_names(&rel_def.to_col),
+            on_delete: rel_def.on_delete,
+            on_update: rel_def.on_update,
+        },
+        _ => panic!(
+            "Sea migrations does not yet support '{:?}' relationships!",
+            rel_def.rel_type
+        ),
+    }
+}
+
+// get_schema_column_def is used to convert a column of a TableSchema into a sea_query ColumnDef.
+pub(crate) fn get_schema_column_def(
+    table: &TableSchema,
+    column: &ColumnSchema,
+    ctx: &SchemaContext,
+) -> ColumnDef {
+    let col_type = match (&column.col_type, ctx.backend) {
+        // Postgres has no tinyint so the smallest serial type is used.
+        (ColumnType::TinyInteger, DbBackend::Postgres) if column.auto_increment => {
+            ColumnType::SmallInteger
+        }
+        (col_type, backend) => get_column_type(col_type, backend),
+    };
+    let mut column_def = ColumnDef::new_with_type(Alias::new(&column.name), col_type.into());
+    if !column.nullable {
         column_def.not_null();
     }
-    if column_def_prelude.unique {
-        column_def.unique_key();
+    if let Some(default) = &column.default {
+        default.apply(&mut column_def);
     }
-    if column_def_prelude.indexed {
-        panic!("Indexed columns are not yet able to be migrated!");
+
+    if let ColumnType::Enum(_, values) = &column.col_type {
+        if ctx.backend != DbBackend::Postgres {
+            column_def.extra(get_enum_check_constraint(
+                ctx,
+                &table.name,
+                &column.name,
+                values,
+            ));
+        }
     }
 
-    if let Some(_) = T::PrimaryKey::from_column(column) {
+    // Composite primary keys are defined on the table by `get_schema_create_def`.
+    if table.is_primary_key(&column.name) && table.primary_key.len() == 1 {
         column_def.primary_key();
+    }
 
-        if T::PrimaryKey::auto_increment() && column_def_prelude.col_type == ColumnType::Integer {
-            column_def.auto_increment();
+    if column.auto_increment {
+        match (ctx.backend, ctx.postgres_auto_increment) {
+            (DbBackend::Postgres, PostgresAutoIncrement::Identity) => {
+                column_def.extra("GENERATED BY DEFAULT AS IDENTITY".into());
+            }
+            // sea_query uses the serial types on Postgres, `AUTO_INCREMENT` on MySQL and `INTEGER PRIMARY KEY AUTOINCREMENT` on SQLite (where every integer type is called `integer`).
+            _ => {
+                column_def.auto_increment();
+            }
         }
     }
 
     column_def
 }
 
-// get_column_foreign_key_def is used to convert between the sea_orm Relation and the sea_query ForeignKey.
-pub(crate) fn get_column_foreign_key_def<T: EntityTrait>(
-    relation: T::Relation,
-) -> ForeignKeyCreateStatement {
-    let rel_def = relation.def();
-    match rel_def.rel_type {
-        RelationType::HasOne => {
-            let mut foreign_key = ForeignKey::create()
-                .from(
-                    table_ref_to_alias(rel_def.from_tbl),
-                    Alias::new(&rel_def.from_col.to_string()),
-                )
-                .to(
-                    table_ref_to_alias(rel_def.to_tbl),
-                    Alias::new(&rel_def.to_col.to_string()),
-                )
-                .to_owned();
-
-            if let Some(fk_action) = rel_def.on_delete {
-                foreign_key.on_delete(fk_action);
+// has_composite_primary_key returns whether the primary key of a sea_orm Entity is made up of multiple columns.
+pub(crate) fn has_composite_primary_key<T: EntityTrait>() -> bool {
+    T::PrimaryKey::iter().count() > 1
+}
+
+// is_auto_increment_column returns whether a sea_orm Column should be auto incremented. Only single column integer primary keys can be auto incremented.
+pub(crate) fn is_auto_increment_column<T: EntityTrait>(column: T::Column) -> bool {
+    T::PrimaryKey::from_column(column).is_some()
+        && !has_composite_primary_key::<T>()
+        && T::PrimaryKey::auto_increment()
+        && matches!(
+            get_column_prelude(column).col_type,
+            ColumnType::TinyInteger
+                | ColumnType::SmallInteger
+                | ColumnType::Integer
+                | ColumnType::BigInteger
+        )
+}
+
+// get_column_type_def is used to convert between the sea_orm Column and a sea_query ColumnDef which only contains the column's type.
+pub(crate) fn get_column_type_def<T: EntityTrait>(
+    column: T::Column,
+    backend: DbBackend,
+) -> ColumnDef {
+    ColumnDef::new_with_type(
+        column,
+        get_column_type(&get_column_prelude(column).col_type, backend).into(),
+    )
+}
+
+// get_column_type returns the type a column is created with on a database backend. Enums use the enum type of the same name on Postgres and are stored as strings on other databases.
+pub(crate) fn get_column_type(col_type: &ColumnType, backend: DbBackend) -> ColumnType {
+    match (col_type, backend) {
+        (ColumnType::Enum(name, _), DbBackend::Postgres) => {
+            ColumnType::Custom(quote_iden(backend, &Alias::new(name)))
+        }
+        (ColumnType::Enum(_, _), _) => ColumnType::String(None),
+        (col_type, _) => col_type.clone(),
+    }
+}
+
+// get_enum_check_constraint returns the column constraint which restricts an enum column stored as a string to the values of the enum.
+pub(crate) fn get_enum_check_constraint(
+    ctx: &SchemaContext,
+    table_name: &str,
+    column_name: &str,
+    values: &[String],
+) -> String {
+    format!(
+        "CONSTRAINT {} CHECK ({} IN ({}))",
+        quote_iden(
+            ctx.backend,
+            &Alias::new(&ctx.naming.check(table_name, &[column_name]))
+        ),
+        quote_iden(ctx.backend, &Alias::new(column_name)),
+        values
+            .iter()
+            .map(|value| quote_string(value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+// get_schema_enum_types returns the name and values of the enums used by the columns of a TableSchema. Columns for which the filter function returns false are skipped.
+pub(crate) fn get_schema_enum_types(
+    table: &TableSchema,
+    include_column: impl Fn(&ColumnSchema) -> bool,
+) -> Vec<(String, Vec<String>)> {
+    let mut enums: Vec<(String, Vec<String>)> = Vec::new();
+    for column in table.columns.iter().filter(|column| include_column(column)) {
+        if let ColumnType::Enum(name, values) = &column.col_type {
+            if !enums.iter().any(|(existing, _)| existing == name) {
+                enums.push((name.clone(), values.clone()));
+            }
+        }
+    }
+    enums
+}
+
+// get_schema_create_def is used to convert a TableSchema into a sea_query TableCreateStatement.
+// The indexes of the table are not part of the statement. They are created using the statements from `get_schema_index_defs`.
+pub(crate) fn get_schema_create_def(
+    table: &TableSchema,
+    ctx: &SchemaContext,
+) -> TableCreateStatement {
+    let mut stmt = Table::create();
+    stmt.table(Alias::new(&table.name));
+
+    for column in &table.columns {
+        stmt.col(&mut get_schema_column_def(table, column, ctx));
+    }
+
+    if table.primary_key.len() > 1 {
+        let mut primary_key = Index::create();
+        for column in &table.primary_key {
+            primary_key.col(Alias::new(column));
+        }
+        stmt.primary_key(&mut primary_key);
+    }
+
+    for foreign_key in &table.foreign_keys {
+        stmt.foreign_key(&mut get_schema_foreign_key_def(
+            &table.name,
+            foreign_key,
+            ctx.naming,
+        ));
+    }
+
+    stmt
+}
+
+// get_schema_index_defs is used to convert the indexes of a TableSchema into sea_query IndexCreateStatements. Indexes for which the filter function returns false are skipped.
+pub(crate) fn get_schema_index_defs(
+    table: &TableSchema,
+    ctx: &SchemaContext,
+    include_index: impl Fn(&IndexSchema) -> bool,
+) -> Vec<IndexCreateStatement> {
+    table
+        .indexes
+        .iter()
+        .filter(|index| include_index(index))
+        .map(|index| {
+            let mut stmt = Index::create();
+            stmt.name(&get_schema_index_name(&table.name, index, ctx.naming))
+                .table(Alias::new(&table.name));
+            for column in &index.columns {
+                stmt.col(Alias::new(column));
+            }
+            if index.unique {
+                stmt.unique();
             }
+            stmt
+        })
+        .collect()
+}
 
-            if let Some(fk_action) = rel_def.on_update {
-                foreign_key.on_update(fk_action);
+// get_schema_index_name returns the name of the index created for an IndexSchema of a table.
+pub(crate) fn get_schema_index_name(
+    table_name: &str,
+    index: &IndexSchema,
+    naming: &dyn NamingConvention,
+) -> String {
+    let columns = index.columns.iter().map(String::as_str).collect::<Vec<_>>();
+    if index.unique {
+        naming.unique(table_name, &columns)
+    } else {
+        naming.index(table_name, &columns)
+    }
+}
+
+// get_expected_table converts a sea_orm Entity into the table `create_table` would create for it so it can be compared with the table in a live database.
+// Column types are rendered for the backend the same way they are when the table is created.
+pub(crate) fn get_expected_table<E: 'static>(ctx: &SchemaContext) -> IntrospectedTable
+where
+    E: EntityTrait,
+{
+    let table = get_table_schema::<E>(|_| true, |_| true);
+
+    let columns = table
+        .columns
+        .iter()
+        .map(|column| {
+            let column_def = get_schema_column_def(&table, column, ctx);
+            IntrospectedColumn {
+                name: column.name.clone(),
+                data_type: column_def
+                    .get_column_type()
+                    .map(|col_type| build_column_type(ctx.backend, col_type))
+                    .unwrap_or_default(),
+                nullable: !column_def
+                    .get_column_spec()
+                    .iter()
+                    .any(|spec| matches!(spec, ColumnSpec::NotNull)),
             }
+        })
+        .collect();
+
+    let foreign_keys = table
+        .foreign_keys
+        .iter()
+        .map(|foreign_key| IntrospectedForeignKey {
+            name: Some(get_schema_foreign_key_name(
+                &table.name,
+                foreign_key,
+                ctx.naming,
+            )),
+            columns: foreign_key.columns.clone(),
+            referenced_table: foreign_key.referenced_table.clone(),
+            referenced_columns: foreign_key.referenced_columns.clone(),
+        })
+        .collect();
+
+    let indexes = table
+        .indexes
+        .iter()
+        .map(|index| IntrospectedIndex {
+            name: get_schema_index_name(&table.name, index, ctx.naming),
+            columns: index.columns.clone(),
+            unique: index.unique,
+        })
+        .collect();
+
+    IntrospectedTable {
+        name: table.name,
+        columns,
+        foreign_keys,
+        indexes,
+    }
+}
+
+// get_managed_index_names returns the names of every index sea_migrations could have created for the columns and indexes of a TableSchema.
+pub(crate) fn get_managed_index_names(
+    table: &TableSchema,
+    naming: &dyn NamingConvention,
+) -> Vec<String> {
+    table
+        .columns
+        .iter()
+        .flat_map(|column| {
+            vec![
+                naming.unique(&table.name, &[column.name.as_str()]),
+                naming.index(&table.name, &[column.name.as_str()]),
+            ]
+        })
+        .chain(
+            table
+                .indexes
+                .iter()
+                .map(|index| get_schema_index_name(&table.name, index, naming)),
+        )
+        .collect()
+}
+
+// get_schema_foreign_key_def is used to convert a ForeignKeySchema of a table into a sea_query ForeignKey.
+pub(crate) fn get_schema_foreign_key_def(
+    table_name: &str,
+    foreign_key: &ForeignKeySchema,
+    naming: &dyn NamingConvention,
+) -> ForeignKeyCreateStatement {
+    let mut stmt = ForeignKey::create();
+    stmt.name(&get_schema_foreign_key_name(
+        table_name,
+        foreign_key,
+        naming,
+    ))
+    .from_tbl(Alias::new(table_name))
+    .to_tbl(Alias::new(&foreign_key.referenced_table));
+    for column in &foreign_key.columns {
+        stmt.from_col(Alias::new(column));
+    }
+    for column in &foreign_key.referenced_columns {
+        stmt.to_col(Alias::new(column));
+    }
+
+    if let Some(fk_action) = foreign_key.on_delete {
+        stmt.on_delete(fk_action);
+    }
+
+    if let Some(fk_action) = foreign_key.on_update {
+        stmt.on_update(fk_action);
+    }
 
-            foreign_key
+    stmt
+}
+
+// get_schema_foreign_key_name returns the name of the foreign key constraint created for a ForeignKeySchema of a table.
+pub(crate) fn get_schema_foreign_key_name(
+    table_name: &str,
+    foreign_key: &ForeignKeySchema,
+    naming: &dyn NamingConvention,
+) -> String {
+    naming.foreign_key(
+        table_name,
+        &foreign_key
+            .columns
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        &foreign_key.referenced_table,
+    )
+}
+
+// get_foreign_key_name returns the name of the foreign key constraint created for a sea_orm Relation.
+pub(crate) fn get_foreign_key_name(rel_def: &RelationDef, naming: &dyn NamingConvention) -> String {
+    let columns = identity_to_column_names(&rel_def.from_col);
+    naming.foreign_key(
+        &table_ref_to_alias(rel_def.from_tbl.clone()).to_string(),
+        &columns.iter().map(String::as_str).collect::<Vec<_>>(),
+        &table_ref_to_alias(rel_def.to_tbl.clone()).to_string(),
+    )
+}
+
+// identity_to_column_names converts a sea_orm Identity into the names of the columns it refers to.
+pub(crate) fn identity_to_column_names(identity: &Identity) -> Vec<String> {
+    match identity {
+        Identity::Unary(iden) => vec![iden.to_string()],
+        Identity::Binary(iden1, iden2) => vec![iden1.to_string(), iden2.to_string()],
+        Identity::Ternary(iden1, iden2, iden3) => {
+            vec![iden1.to_string(), iden2.to_string(), iden3.to_string()]
         }
-        _ => panic!(
-            "Sea migrations does not yet support '{:?}' relationships!",
-            rel_def.rel_type
-        ),
     }
 }
 
@@ -90,3 +477,78 @@ pub(crate) fn table_ref_to_alias(table_ref: TableRef) -> Alias {
         ),
     }
 }
+
+// quote_iden quotes an identifier so it can be used in a raw SQL statement for the given database backend.
+pub(crate) fn quote_iden(backend: DbBackend, iden: &dyn Iden) -> String {
+    let quote = match backend {
+        DbBackend::Postgres => '"',
+        DbBackend::MySql | DbBackend::Sqlite => '`',
+    };
+    format!("{}{}{}", quote, iden.quoted(quote), quote)
+}
+
+// quote_string quotes a string so it can be used as a literal in a raw SQL statement.
+pub(crate) fn quote_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// build_schema_stmt converts a sea_query schema statement into a Statement for the given database backend. This is needed for the statements SeaORM can't build itself such as foreign keys and indexes.
+pub(crate) fn build_schema_stmt<S: SchemaStatementBuilder>(
+    backend: DbBackend,
+    stmt: &S,
+) -> Statement {
+    let sql = match backend {
+        DbBackend::MySql => stmt.to_string(MysqlQueryBuilder),
+        DbBackend::Postgres => stmt.to_string(PostgresQueryBuilder),
+        DbBackend::Sqlite => stmt.to_string(SqliteQueryBuilder),
+    };
+    Statement::from_string(backend, sql)
+}
+
+// build_index_if_not_exists_stmt converts a sea_query index statement into a `CREATE INDEX IF NOT EXISTS` statement. sea_query can't build these and they aren't supported by MySQL.
+pub(crate) fn build_index_if_not_exists_stmt(
+    backend: DbBackend,
+    stmt: &IndexCreateStatement,
+) -> Statement {
+    let sql = build_schema_stmt(backend, stmt)
+        .sql
+        .replacen("INDEX ", "INDEX IF NOT EXISTS ", 1);
+    Statement::from_string(backend, sql)
+}
+
+// build_query_sql converts a sea_query query into SQL for the given database backend with the values inlined. This is used to embed queries into schema statements such as views.
+pub(crate) fn build_query_sql<S: QueryStatementBuilder>(backend: DbBackend, stmt: &S) -> String {
+    match backend {
+        DbBackend::MySql => stmt.to_string(MysqlQueryBuilder),
+        DbBackend::Postgres => stmt.to_string(PostgresQueryBuilder),
+        DbBackend::Sqlite => stmt.to_string(SqliteQueryBuilder),
+    }
+}
+
+// build_simple_expr converts a sea_query expression into SQL for the given database backend. Values are inlined as schema statements such as check constraints can't contain parameters.
+pub(crate) fn build_simple_expr(backend: DbBackend, expr: &SimpleExpr) -> String {
+    let query_builder: &dyn QueryBuilder = match backend {
+        DbBackend::MySql => &MysqlQueryBuilder,
+        DbBackend::Postgres => &PostgresQueryBuilder,
+        DbBackend::Sqlite => &SqliteQueryBuilder,
+    };
+    let mut sql = SqlWriter::new();
+    let mut values = Vec::new();
+    query_builder.prepare_simple_expr(expr, &mut sql, &mut |value| values.push(value));
+    inject_parameters(&sql.result(), values, query_builder)
+}
+
+// build_column_type converts a sea_query column type into the name of the type for the given database backend. Auto incrementing types such as `serial` are not used.
+pub(crate) fn build_column_type(
+    backend: DbBackend,
+    col_type: &sea_orm::sea_query::ColumnType,
+) -> String {
+    let table_builder: &dyn TableBuilder = match backend {
+        DbBackend::MySql => &MysqlQueryBuilder,
+        DbBackend::Postgres => &PostgresQueryBuilder,
+        DbBackend::Sqlite => &SqliteQueryBuilder,
+    };
+    let mut sql = SqlWriter::new();
+    table_builder.prepare_column_type(col_type, &mut sql);
+    sql.result()
+}
@@ -0,0 +1,300 @@
+use async_trait::async_trait;
+use std::{borrow::Cow, collections::BTreeMap, fs, mem, path::Path};
+
+use sea_orm::{ConnectionTrait, DbErr};
+
+use sea_migrations_common::{check_sql_files, SqlBackend, SqlFile};
+
+use crate::{BackendSql, MigrationManager, MigrationName, MigratorTrait};
+
+/// SqlMigration is a migration written in SQL. The `up` and `down` SQL can contain multiple statements separated by semicolons which are run one after another.
+/// SqlMigrations are normally created from `.up.sql` and `.down.sql` files using the `embed_migrations!` macro. They are sorted together with your Rust migrations by name so SQL files should be named the same way (eg. `M20210101020202DoAThing.up.sql`).
+/// SQL which only works on one database backend can be put in a file for that backend (eg. `M20210101020202DoAThing.postgres.up.sql`). It is used instead of the file without a backend when running on that database.
+///
+/// ```rust
+/// use sea_migrations::{Migrator, MigratorTrait, SqlMigration};
+/// use sea_orm::{Database, DbErr};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DbErr> {
+///     let db = Database::connect("sqlite::memory:").await?;
+///
+///     let mut migrations: Vec<Box<dyn MigratorTrait>> = vec![Box::new(SqlMigration::new(
+///         "M20210101020202CreateBakery",
+///         "CREATE TABLE bakery (id INTEGER PRIMARY KEY, name TEXT NOT NULL); CREATE INDEX idx_bakery_name ON bakery (name);",
+///         Some("DROP TABLE bakery;"),
+///     ))];
+///     // migrations.extend(sea_migrations::embed_migrations!("migrations/")); // Load the SQL files in the "migrations" directory of your crate.
+///
+///     Migrator::run(&db, &mut migrations).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlMigration {
+    name: &'static str,
+    up: BackendSql,
+    down: Option<BackendSql>,
+}
+
+impl SqlMigration {
+    /// new creates a SqlMigration from it's name and SQL which is used on every database backend. If `down` is None the migration can't be undone.
+    pub fn new<N, U, D>(name: N, up: U, down: Option<D>) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+        U: Into<String>,
+        D: Into<String>,
+    {
+        Self::with_backend_sql(name, BackendSql::all(up), down.map(BackendSql::all))
+    }
+
+    /// with_backend_sql creates a SqlMigration from it's name and a variant of the SQL for each database backend. An error is returned when the migration is run on a database backend without a variant.
+    /// Migration names are `&'static str` so a name which is an owned String (eg. one read by `load_dir`) is leaked. Migrations are normally only created once when the application starts.
+    pub fn with_backend_sql<N>(name: N, up: BackendSql, down: Option<BackendSql>) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        let name = match name.into() {
+            Cow::Borrowed(name) => name,
+            Cow::Owned(name) => Box::leak(name.into_boxed_str()),
+        };
+
+        Self { name, up, down }
+    }
+
+    /// load_dir reads the SQL migrations in a directory at runtime. The files are named the same way as for the `embed_migrations!` macro.
+    /// Prefer `embed_migrations!` in your application so the migrations are built into the binary. This is useful for tools which are given the directory when they are run.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Vec<Self>, DbErr> {
+        let dir = dir.as_ref();
+        let read_error = |err: std::io::Error| {
+            DbErr::Custom(format!(
+                "Failed to read the migrations directory '{}': {}",
+                dir.display(),
+                err
+            ))
+        };
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir).map_err(read_error)? {
+            let entry = entry.map_err(read_error)?;
+            let file_name = match entry.file_name().to_str() {
+                Some(file_name) if SqlFile::parse(file_name).is_some() => file_name.to_string(),
+                _ => continue,
+            };
+            files.push((
+                file_name,
+                fs::read_to_string(entry.path()).map_err(read_error)?,
+            ));
+        }
+
+        Self::from_files(files)
+    }
+
+    /// from_files creates the SqlMigrations from the names and contents of their SQL files. The files are named the same way as for the `embed_migrations!` macro and files with any other name are skipped.
+    /// It is used by `load_dir`. An error is returned if a migration has no `.up.sql` file. The `embed_migrations!` macro makes the same check when your crate is compiled.
+    ///
+    /// ```rust
+    /// use sea_migrations::{MigrationName, SqlMigration};
+    ///
+    /// let migrations = SqlMigration::from_files(vec![
+    ///     ("M20210101020202CreateBakery.up.sql", "CREATE TABLE bakery (id INTEGER PRIMARY KEY);"),
+    ///     ("M20210101020202CreateBakery.postgres.up.sql", "CREATE TABLE bakery (id SERIAL PRIMARY KEY);"),
+    ///     ("M20210101020202CreateBakery.down.sql", "DROP TABLE bakery;"),
+    ///     ("README.md", "Not a migration."),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(migrations.len(), 1);
+    /// assert_eq!(migrations[0].name(), "M20210101020202CreateBakery");
+    ///
+    /// assert!(SqlMigration::from_files(vec![("M20210101020202CreateBakery.down.sql", "DROP TABLE bakery;")]).is_err());
+    /// ```
+    pub fn from_files<N, S>(files: impl IntoIterator<Item = (N, S)>) -> Result<Vec<Self>, DbErr>
+    where
+        N: Into<Cow<'static, str>>,
+        S: Into<String>,
+    {
+        let files = files
+            .into_iter()
+            .map(|(file_name, sql)| (file_name.into(), sql.into()))
+            .collect::<Vec<(Cow<'static, str>, String)>>();
+        check_sql_files(files.iter().map(|(file_name, _)| file_name.as_ref()))
+            .map_err(DbErr::Custom)?;
+
+        // migrations maps the name of each migration to it's up and down SQL.
+        let mut migrations = BTreeMap::<Cow<'static, str>, (BackendSql, Option<BackendSql>)>::new();
+        for (file_name, sql) in files {
+            let (name, file) = match &file_name {
+                Cow::Borrowed(file_name) => match SqlFile::parse(file_name) {
+                    Some(file) => (Cow::Borrowed(file.migration), file),
+                    None => continue,
+                },
+                Cow::Owned(file_name) => match SqlFile::parse(file_name) {
+                    Some(file) => (Cow::Owned(file.migration.to_string()), file),
+                    None => continue,
+                },
+            };
+
+            let (up, down) = migrations.entry(name).or_default();
+            let slot = if file.is_up {
+                up
+            } else {
+                down.get_or_insert_with(BackendSql::default)
+            };
+            let variants = mem::take(slot);
+            *slot = match file.backend {
+                Some(SqlBackend::Postgres) => variants.postgres(sql),
+                Some(SqlBackend::MySql) => variants.mysql(sql),
+                Some(SqlBackend::Sqlite) => variants.sqlite(sql),
+                None => variants.fallback(sql),
+            };
+        }
+
+        Ok(migrations
+            .into_iter()
+            .map(|(name, (up, down))| Self::with_backend_sql(name, up, down))
+            .collect())
+    }
+
+    // execute runs each of the statements in the variant of a SQL script for the database backend.
+    async fn execute(&self, mg: &MigrationManager<'_>, sql: &BackendSql) -> Result<(), DbErr> {
+        let backend = mg.db.get_database_backend();
+        let sql = sql.get(backend).map_err(|_| {
+            DbErr::Custom(format!(
+                "The migration '{}' has no SQL for the {:?} database backend!",
+                self.name, backend
+            ))
+        })?;
+        for statement in split_statements(sql) {
+            mg.execute_sql(statement).await?;
+        }
+        Ok(())
+    }
+}
+
+impl MigrationName for SqlMigration {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[async_trait]
+impl MigratorTrait for SqlMigration {
+    async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+        self.execute(mg, &self.up).await
+    }
+
+    async fn down(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+        match &self.down {
+            Some(down) => self.execute(mg, down).await,
+            None => Err(DbErr::Custom(format!(
+                "The migration '{}' has no down migration!",
+                self.name
+            ))),
+        }
+    }
+}
+
+// split_statements splits a SQL script into it's statements. Semicolons inside of strings, quoted identifiers, comments, Postgres dollar quoted strings and the `BEGIN ... END` body of triggers, functions and procedures don't end a statement.
+pub(crate) fn split_statements(sql: &str) -> Vec<String> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    let mut first_word = None;
+    let mut is_block_statement = false;
+    let mut block_depth = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                i = find_from(sql, i + 1, &(quote as char).to_string())
+                    .map_or(bytes.len(), |end| end + 1);
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = find_from(sql, i, "\n").map_or(bytes.len(), |end| end + 1);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = find_from(sql, i + 2, "*/").map_or(bytes.len(), |end| end + 2);
+            }
+            b'$' => {
+                let tag_end = sql[i + 1..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .map(|end| i + 1 + end);
+                match tag_end {
+                    // `$1` is a parameter not a dollar quote.
+                    Some(tag_end)
+                        if bytes[tag_end] == b'$'
+                            && !bytes[i + 1..tag_end]
+                                .first()
+                                .is_some_and(u8::is_ascii_digit) =>
+                    {
+                        let tag = &sql[i..=tag_end];
+                        i = find_from(sql, tag_end + 1, tag)
+                            .map_or(bytes.len(), |end| end + tag.len());
+                    }
+                    _ => i += 1,
+                }
+            }
+            b';' if block_depth <= 0 => {
+                push_statement(&mut statements, &sql[start..i]);
+                start = i + 1;
+                i += 1;
+                first_word = None;
+                is_block_statement = false;
+                block_depth = 0;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let end = sql[i..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .map_or(bytes.len(), |end| i + end);
+                let word = sql[i..end].to_ascii_uppercase();
+                i = end;
+
+                let first_word = first_word.get_or_insert_with(|| word.clone());
+                if first_word == "CREATE"
+                    && matches!(word.as_str(), "TRIGGER" | "FUNCTION" | "PROCEDURE")
+                {
+                    is_block_statement = true;
+                } else if is_block_statement {
+                    match word.as_str() {
+                        "BEGIN" | "CASE" => block_depth += 1,
+                        // `END IF`, `END LOOP`, etc close blocks which weren't counted when they were opened.
+                        "END" => {
+                            let next_word = sql[i..]
+                                .trim_start()
+                                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                                .next()
+                                .unwrap_or("")
+                                .to_ascii_uppercase();
+                            if !matches!(next_word.as_str(), "IF" | "LOOP" | "WHILE" | "REPEAT") {
+                                block_depth -= 1;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    push_statement(&mut statements, &sql[start..]);
+
+    statements
+}
+
+// find_from finds the first occurrence of a pattern at or after the byte offset `from`.
+fn find_from(sql: &str, from: usize, pattern: &str) -> Option<usize> {
+    sql.get(from..)?.find(pattern).map(|i| from + i)
+}
+
+// push_statement adds a statement to the list of statements unless it only contains whitespace and comments.
+fn push_statement(statements: &mut Vec<String>, statement: &str) {
+    let is_empty = statement
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or("").trim())
+        .all(str::is_empty);
+    if !is_empty {
+        statements.push(statement.trim().to_string());
+    }
+}
@@ -0,0 +1,496 @@
+use std::{future::Future, pin::Pin};
+
+use sea_orm::{
+    sea_query::{Alias, IndexCreateStatement, Table, TableCreateStatement},
+    ConnectionTrait, DatabaseTransaction, DbBackend, DbConn, DbErr, ExecResult, Statement,
+};
+
+use crate::{
+    catch_unwind::catch_unwind,
+    seaorm_integration::{build_schema_stmt, quote_iden},
+};
+
+// SQLITE_REBUILD_TABLE_PREFIX is prepended to the name of a table to get the name of the temporary table used while rebuilding it.
+const SQLITE_REBUILD_TABLE_PREFIX: &str = "_sea_migrations_new_";
+
+// SQLITE_RENAME_COLUMN_VERSION is the first version of SQLite which supports `ALTER TABLE ... RENAME COLUMN`.
+const SQLITE_RENAME_COLUMN_VERSION: (u32, u32, u32) = (3, 25, 0);
+
+// sqlite_version returns the version of the SQLite library the database connection is using as a (major, minor, patch) tuple.
+pub(crate) async fn sqlite_version(db: &DbConn) -> Result<(u32, u32, u32), DbErr> {
+    let result = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT sqlite_version() AS version".into(),
+        ))
+        .await?
+        .ok_or_else(|| DbErr::Query("Failed to determine the SQLite version!".into()))?;
+    let version: String = result.try_get("", "version")?;
+
+    let mut parts = version
+        .split('.')
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    Ok((
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    ))
+}
+
+// supports_rename_column returns whether the SQLite database supports the `RENAME COLUMN` statement.
+pub(crate) async fn supports_rename_column(db: &DbConn) -> Result<bool, DbErr> {
+    Ok(sqlite_version(db).await? >= SQLITE_RENAME_COLUMN_VERSION)
+}
+
+// rebuild_table applies changes to a table which SQLite's `ALTER TABLE` can't make using the table rebuild procedure from https://www.sqlite.org/lang_altertable.html#otheralter.
+// The new table is created from `create_stmt` (the table name it contains is replaced) and `column_mapping` is a list of (new column, old column) pairs for the data to copy. Pairs where the old column doesn't exist are skipped.
+// Existing indexes, triggers and table check constraints are recreated except for the indexes named in `managed_indexes` which are replaced by the indexes in `index_stmts`.
+// The result of copying the data into the new table is returned.
+pub(crate) async fn rebuild_table(
+    db: &DbConn,
+    table_name: &str,
+    mut create_stmt: TableCreateStatement,
+    column_mapping: Vec<(String, String)>,
+    index_stmts: Vec<IndexCreateStatement>,
+    managed_indexes: Vec<String>,
+) -> Result<ExecResult, DbErr> {
+    let backend = db.get_database_backend();
+
+    // Check constraints can't be described by a SeaORM Entity so they are copied from the existing table.
+    let old_table = TableSql::parse(&table_sql(db, table_name).await?)?;
+    create_stmt.table(Alias::new(&rebuild_table_name(table_name)));
+    let mut new_table = TableSql::parse(&build_schema_stmt(backend, &create_stmt).sql)?;
+    new_table.definitions.extend(
+        old_table
+            .definitions
+            .into_iter()
+            .filter(|definition| is_check_constraint(definition)),
+    );
+
+    rebuild(
+        db,
+        table_name,
+        new_table.to_sql(),
+        Some(column_mapping),
+        index_stmts,
+        managed_indexes,
+    )
+    .await
+}
+
+// alter_table_definitions rebuilds a table after changing the column and constraint definitions from it's `CREATE TABLE` statement. This is used to add and drop table constraints.
+// All of the data, indexes and triggers of the table are kept.
+pub(crate) async fn alter_table_definitions(
+    db: &DbConn,
+    table_name: &str,
+    alter: impl FnOnce(&mut Vec<String>) -> Result<(), DbErr>,
+) -> Result<ExecResult, DbErr> {
+    let mut table = TableSql::parse(&table_sql(db, table_name).await?)?;
+    alter(&mut table.definitions)?;
+    table.name = quote_iden(
+        db.get_database_backend(),
+        &Alias::new(&rebuild_table_name(table_name)),
+    );
+
+    rebuild(db, table_name, table.to_sql(), None, Vec::new(), Vec::new()).await
+}
+
+// column_defaults returns the SQL of the default value of each column of a table which has one.
+pub(crate) async fn column_defaults(
+    db: &DbConn,
+    table_name: &str,
+) -> Result<Vec<(String, String)>, DbErr> {
+    let backend = db.get_database_backend();
+    db.query_all(Statement::from_string(
+        backend,
+        format!(
+            "PRAGMA table_info({})",
+            quote_iden(backend, &Alias::new(table_name))
+        ),
+    ))
+    .await?
+    .iter()
+    .filter_map(|row| {
+        let default = match row.try_get::<Option<String>>("", "dflt_value") {
+            Ok(default) => default?,
+            Err(err) => return Some(Err(err)),
+        };
+        Some(
+            row.try_get::<String>("", "name")
+                .map(|name| (name, default)),
+        )
+    })
+    .collect()
+}
+
+// constraint_name returns the name of a table constraint definition such as `CONSTRAINT "name" CHECK (...)`. Unnamed constraints and column definitions return None.
+pub(crate) fn constraint_name(definition: &str) -> Option<String> {
+    let rest = strip_keyword(definition.trim_start(), "CONSTRAINT")?;
+    let (name, _) = split_name(rest.trim_start());
+    Some(name)
+}
+
+// is_check_constraint returns whether a definition from a `CREATE TABLE` statement is a (named or unnamed) table check constraint.
+fn is_check_constraint(definition: &str) -> bool {
+    let definition = definition.trim_start();
+    let definition = match strip_keyword(definition, "CONSTRAINT") {
+        Some(rest) => split_name(rest.trim_start()).1.trim_start(),
+        None => definition,
+    };
+    strip_keyword(definition, "CHECK").is_some()
+}
+
+// strip_keyword removes a keyword from the start of some SQL ignoring it's case.
+fn strip_keyword<'a>(sql: &'a str, keyword: &str) -> Option<&'a str> {
+    let (head, rest) = (sql.get(..keyword.len())?, sql.get(keyword.len()..)?);
+    let is_word_end = !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+    if head.eq_ignore_ascii_case(keyword) && is_word_end {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+// split_name splits an (optionally quoted) identifier from the start of some SQL. The unquoted name and the remaining SQL are returned.
+fn split_name(sql: &str) -> (String, &str) {
+    let close = match sql.chars().next() {
+        Some('"') => '"',
+        Some('`') => '`',
+        Some('[') => ']',
+        Some('\'') => '\'',
+        _ => {
+            let end = sql
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(sql.len());
+            return (sql[..end].to_string(), &sql[end..]);
+        }
+    };
+    match sql[1..].find(close) {
+        Some(end) => (sql[1..end + 1].to_string(), &sql[end + 2..]),
+        None => (sql[1..].to_string(), ""),
+    }
+}
+
+// TableSql is a `CREATE TABLE` statement split into the table name, the column and constraint definitions and the table options which follow them.
+struct TableSql {
+    name: String,
+    definitions: Vec<String>,
+    options: String,
+}
+
+impl TableSql {
+    // parse splits a `CREATE TABLE` statement as stored in `sqlite_master`.
+    fn parse(sql: &str) -> Result<Self, DbErr> {
+        let invalid = || DbErr::Custom(format!("Failed to parse the table definition '{}'!", sql));
+        let start = find_unquoted(sql, 0, |c| c == '(').ok_or_else(invalid)?;
+        let mut rest = strip_keyword(sql.trim_start(), "CREATE")
+            .and_then(|rest| strip_keyword(rest.trim_start(), "TABLE"))
+            .ok_or_else(invalid)?;
+        for keyword in ["IF", "NOT", "EXISTS"] {
+            rest = strip_keyword(rest.trim_start(), keyword).unwrap_or(rest);
+        }
+        let name = sql[sql.len() - rest.len()..start].trim().to_string();
+
+        let mut definitions = Vec::new();
+        let mut depth = 0;
+        let mut definition_start = start + 1;
+        let mut position = start + 1;
+        loop {
+            position = find_unquoted(sql, position, |c| matches!(c, '(' | ')' | ','))
+                .ok_or_else(invalid)?;
+            match &sql[position..position + 1] {
+                "(" => depth += 1,
+                ")" if depth > 0 => depth -= 1,
+                "," if depth > 0 => {}
+                separator => {
+                    definitions.push(sql[definition_start..position].trim().to_string());
+                    definition_start = position + 1;
+                    if separator == ")" {
+                        break;
+                    }
+                }
+            }
+            position += 1;
+        }
+
+        Ok(Self {
+            name,
+            definitions,
+            options: sql[position + 1..].trim().to_string(),
+        })
+    }
+
+    // to_sql joins the parts of the statement back into a `CREATE TABLE` statement.
+    fn to_sql(&self) -> String {
+        format!(
+            "CREATE TABLE {} ({}) {}",
+            self.name,
+            self.definitions.join(", "),
+            self.options
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+// find_unquoted finds the first character matching the pattern at or after `from` which is not inside a quoted string or identifier.
+fn find_unquoted(sql: &str, from: usize, pattern: impl Fn(char) -> bool) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in sql[from..].char_indices() {
+        match quote {
+            Some(close) if c == close => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '[' => quote = Some(']'),
+                c if pattern(c) => return Some(from + i),
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+// table_sql returns the `CREATE TABLE` statement of a table.
+async fn table_sql(db: &DbConn, table_name: &str) -> Result<String, DbErr> {
+    db.query_one(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?",
+        vec![table_name.into()],
+    ))
+    .await?
+    .ok_or_else(|| DbErr::Custom(format!("The table '{}' does not exist!", table_name)))?
+    .try_get("", "sql")
+}
+
+// rebuild_table_name returns the name of the temporary table used while rebuilding a table.
+fn rebuild_table_name(table_name: &str) -> String {
+    format!("{}{}", SQLITE_REBUILD_TABLE_PREFIX, table_name)
+}
+
+// rebuild replaces a table with a new table created using `create_sql` and copies the data across. A `column_mapping` of None copies every column to the column of the same name.
+async fn rebuild(
+    db: &DbConn,
+    table_name: &str,
+    create_sql: String,
+    column_mapping: Option<Vec<(String, String)>>,
+    index_stmts: Vec<IndexCreateStatement>,
+    managed_indexes: Vec<String>,
+) -> Result<ExecResult, DbErr> {
+    // Foreign key enforcement must be disabled as the old table is temporarily dropped.
+    let table_name = table_name.to_string();
+    without_foreign_keys(db, move |txn| {
+        Box::pin(async move {
+            rebuild_table_in_txn(
+                txn,
+                &table_name,
+                create_sql,
+                column_mapping,
+                index_stmts,
+                managed_indexes,
+            )
+            .await
+        })
+    })
+    .await
+}
+
+// without_foreign_keys runs `f` in a transaction with foreign key enforcement disabled. The transaction is rolled back if `f` returns an error or panics and foreign key enforcement is always restored afterwards.
+// `PRAGMA foreign_keys` only affects the connection it is run on and is ignored inside of a transaction. SeaORM doesn't expose the connections of the pool so a transaction is started to hold on to a single connection and is then ended so the pragma can be changed on that connection.
+// Restoring foreign key enforcement requires running a statement so it can't be done if the returned future is dropped before it completes. SeaORM rolls back the open transaction when the connection is returned to the pool but foreign key enforcement stays disabled on that connection until it is closed.
+pub(crate) async fn without_foreign_keys<T, F>(db: &DbConn, f: F) -> Result<T, DbErr>
+where
+    F: for<'c> FnOnce(
+        &'c DatabaseTransaction,
+    ) -> Pin<Box<dyn Future<Output = Result<T, DbErr>> + Send + 'c>>,
+{
+    let conn = db.begin().await?;
+    execute(&conn, "COMMIT").await?;
+
+    let result = run_without_foreign_keys(&conn, f).await;
+
+    // A transaction is started again so the transaction which holds the connection can be committed.
+    let end_result = match execute(&conn, "BEGIN").await {
+        Ok(_) => conn.commit().await,
+        Err(err) => Err(err),
+    };
+    let result = result?;
+    end_result?;
+    Ok(result)
+}
+
+// run_without_foreign_keys does the work of `without_foreign_keys` on the connection held by `conn` while it isn't in a transaction.
+async fn run_without_foreign_keys<T, F>(conn: &DatabaseTransaction, f: F) -> Result<T, DbErr>
+where
+    F: for<'c> FnOnce(
+        &'c DatabaseTransaction,
+    ) -> Pin<Box<dyn Future<Output = Result<T, DbErr>> + Send + 'c>>,
+{
+    let foreign_keys_enabled = match conn
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "PRAGMA foreign_keys".into(),
+        ))
+        .await?
+    {
+        Some(result) => result.try_get::<i32>("", "foreign_keys")? == 1,
+        None => false,
+    };
+    if foreign_keys_enabled {
+        execute(conn, "PRAGMA foreign_keys = OFF").await?;
+    }
+
+    let result = match execute(conn, "BEGIN").await {
+        Ok(_) => match catch_unwind(f(conn)).await {
+            Ok(value) => match execute(conn, "COMMIT").await {
+                Ok(_) => Ok(value),
+                Err(err) => {
+                    execute(conn, "ROLLBACK").await.ok();
+                    Err(err)
+                }
+            },
+            Err(err) => {
+                // The error from `f` is more useful than an error rolling back so it is returned instead.
+                execute(conn, "ROLLBACK").await.ok();
+                Err(err)
+            }
+        },
+        Err(err) => Err(err),
+    };
+
+    if foreign_keys_enabled {
+        let restore_result = execute(conn, "PRAGMA foreign_keys = ON").await;
+        let value = result?;
+        restore_result?;
+        return Ok(value);
+    }
+
+    result
+}
+
+// execute runs a raw SQL statement on the connection held by a transaction.
+async fn execute(conn: &DatabaseTransaction, sql: &str) -> Result<ExecResult, DbErr> {
+    conn.execute(Statement::from_string(DbBackend::Sqlite, sql.into()))
+        .await
+}
+
+// rebuild_table_in_txn does the work of `rebuild` inside of the transaction.
+async fn rebuild_table_in_txn(
+    txn: &DatabaseTransaction,
+    table_name: &str,
+    create_sql: String,
+    column_mapping: Option<Vec<(String, String)>>,
+    index_stmts: Vec<IndexCreateStatement>,
+    managed_indexes: Vec<String>,
+) -> Result<ExecResult, DbErr> {
+    let backend = txn.get_database_backend();
+    let table = Alias::new(table_name);
+    let new_table = Alias::new(&rebuild_table_name(table_name));
+
+    // Save the indexes and triggers on the table so they can be recreated. Indexes created automatically for constraints have no SQL and are recreated with the table.
+    // Every view (and the triggers on them) is also saved as SQLite checks the views still work when the table is renamed and a view could refer to the table while it doesn't exist. They are recreated in the order they were originally created so views using other views still work.
+    let schema_objects = txn
+        .query_all(Statement::from_sql_and_values(
+            backend,
+            "SELECT type, name, sql FROM sqlite_master WHERE sql IS NOT NULL AND (type = 'view' OR (type IN ('index', 'trigger') AND tbl_name = ?) OR (type = 'trigger' AND tbl_name IN (SELECT name FROM sqlite_master WHERE type = 'view'))) ORDER BY type = 'view' DESC, rowid",
+            vec![table_name.into()],
+        ))
+        .await?
+        .iter()
+        .map(|row| {
+            Ok((
+                row.try_get::<String>("", "type")?,
+                row.try_get::<String>("", "name")?,
+                row.try_get::<String>("", "sql")?,
+            ))
+        })
+        .collect::<Result<Vec<_>, DbErr>>()?
+        .into_iter()
+        .filter(|(object_type, name, _)| object_type != "index" || !managed_indexes.contains(name))
+        .collect::<Vec<_>>();
+
+    let existing_columns = txn
+        .query_all(Statement::from_string(
+            backend,
+            format!("PRAGMA table_info({})", quote_iden(backend, &table)),
+        ))
+        .await?
+        .iter()
+        .map(|row| row.try_get::<String>("", "name"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let column_mapping = column_mapping.unwrap_or_else(|| {
+        existing_columns
+            .iter()
+            .map(|column| (column.clone(), column.clone()))
+            .collect()
+    });
+    let (new_columns, old_columns): (Vec<_>, Vec<_>) = column_mapping
+        .into_iter()
+        .filter(|(_, old_column)| existing_columns.contains(old_column))
+        .map(|(new_column, old_column)| {
+            (
+                quote_iden(backend, &Alias::new(&new_column)),
+                quote_iden(backend, &Alias::new(&old_column)),
+            )
+        })
+        .unzip();
+
+    txn.execute(Statement::from_string(backend, create_sql))
+        .await?;
+
+    let exec_result = txn
+        .execute(Statement::from_string(
+            backend,
+            format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {}",
+                quote_iden(backend, &new_table),
+                new_columns.join(", "),
+                old_columns.join(", "),
+                quote_iden(backend, &table)
+            ),
+        ))
+        .await?;
+
+    for (_, name, _) in schema_objects
+        .iter()
+        .filter(|(object_type, _, _)| object_type == "view")
+    {
+        txn.execute(Statement::from_string(
+            backend,
+            format!("DROP VIEW {}", quote_iden(backend, &Alias::new(name))),
+        ))
+        .await?;
+    }
+
+    let stmt = Table::drop().table(table.clone()).to_owned();
+    txn.execute(backend.build(&stmt)).await?;
+
+    let stmt = Table::rename().table(new_table, table).to_owned();
+    txn.execute(backend.build(&stmt)).await?;
+
+    for (_, _, sql) in schema_objects {
+        txn.execute(Statement::from_string(backend, sql)).await?;
+    }
+
+    for stmt in index_stmts {
+        txn.execute(build_schema_stmt(backend, &stmt)).await?;
+    }
+
+    let foreign_key_violations = txn
+        .query_all(Statement::from_string(
+            backend,
+            format!(
+                "PRAGMA foreign_key_check({})",
+                quote_iden(backend, &Alias::new(table_name))
+            ),
+        ))
+        .await?;
+    if !foreign_key_violations.is_empty() {
+        return Err(DbErr::Custom(format!(
+            "Rebuilding the table '{}' would violate it's foreign key constraints!",
+            table_name
+        )));
+    }
+
+    Ok(exec_result)
+}
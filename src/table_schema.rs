@@ -0,0 +1,430 @@
+use sea_orm::{ColumnType, DbBackend, DbErr, EntityTrait, ForeignKeyAction, Value};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{seaorm_integration::get_table_schema, ColumnDefault};
+
+/// TableSchema is a frozen description of a database table. It can be captured from a SeaORM Entity using [TableSchema::from_entity] and used with `create_table_from_schema`, `add_column_from_schema` and the other `_from_schema` helpers on the MigrationManager.
+/// Migrations which use a TableSchema instead of the Entity keep creating the same table when the Entity is changed later on. Use [TableSchema::to_rust_source] to render a snapshot which can be pasted into the migration when it is written.
+/// With the `serde` feature enabled a TableSchema can be serialized so it can be stored as JSON or YAML (eg. using `serde_json`) and loaded again by a migration.
+///
+/// ```rust
+/// use sea_orm::{entity::prelude::*, Database};
+/// use sea_migrations::{MigrationManager, TableSchema};
+///
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+/// #[sea_orm(table_name = "cake")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     pub name: String,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+/// pub enum Relation {}
+///
+/// impl ActiveModelBehavior for ActiveModel {}
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DbErr> {
+///     let db = Database::connect("sqlite::memory:").await?;
+///     // You would not normally create a MigrationManager by yourself. It would be provided to the `up` or `down` function by sea_migrations.
+///     let mg = MigrationManager::new(&db);
+///
+///     let table = TableSchema::from_entity(Entity);
+///     assert!(table.to_rust_source().starts_with("sea_migrations::TableSchema {"));
+///
+///     mg.create_table_from_schema(&table).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TableSchema {
+    /// name is the name of the table.
+    pub name: String,
+    /// columns are the columns of the table in the order they are created.
+    pub columns: Vec<ColumnSchema>,
+    /// primary_key is the names of the columns which make up the primary key of the table.
+    pub primary_key: Vec<String>,
+    /// foreign_keys are the foreign key constraints of the table.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub foreign_keys: Vec<ForeignKeySchema>,
+    /// indexes are the indexes of the table. The primary key is not included.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub indexes: Vec<IndexSchema>,
+}
+
+/// ColumnSchema is a frozen description of a column in a [TableSchema].
+///
+/// ```rust
+/// use sea_orm::{ColumnType, ConnectionTrait, Database, DbBackend, DbErr, Statement};
+/// use sea_migrations::{ColumnDefault, ColumnSchema, IndexSchema, MigrationManager, TableSchema};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DbErr> {
+///     let db = Database::connect("sqlite::memory:").await?;
+///     let mg = MigrationManager::new(&db);
+///
+///     let table = TableSchema {
+///         name: "cake".into(),
+///         columns: vec![
+///             ColumnSchema { name: "id".into(), col_type: ColumnType::Integer, nullable: false, default: None, auto_increment: true },
+///             ColumnSchema { name: "name".into(), col_type: ColumnType::String(None), nullable: false, default: Some(ColumnDefault::value("Sponge")), auto_increment: false },
+///         ],
+///         primary_key: vec!["id".into()],
+///         foreign_keys: vec![],
+///         indexes: vec![IndexSchema { columns: vec!["name".into()], unique: true }],
+///     };
+///     mg.create_table_from_schema(&table).await?;
+///
+///     db.execute(Statement::from_string(DbBackend::Sqlite, "INSERT INTO cake DEFAULT VALUES".into())).await?;
+///     let row = db.query_one(Statement::from_string(DbBackend::Sqlite, "SELECT name FROM cake".into())).await?.unwrap();
+///     assert_eq!(row.try_get::<String>("", "name")?, "Sponge");
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ColumnSchema {
+    /// name is the name of the column.
+    pub name: String,
+    /// col_type is the SeaORM type of the column. It is converted into the type for the database backend when the column is created.
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::ColumnTypeDef"))]
+    pub col_type: ColumnType,
+    /// nullable is whether the column can contain `NULL`.
+    pub nullable: bool,
+    /// default is the default value of the column. SeaORM Entities can't describe default values so it is always None for a TableSchema captured using [TableSchema::from_entity].
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "serde_remote::option_column_default"
+        )
+    )]
+    pub default: Option<ColumnDefault>,
+    /// auto_increment is whether the column is an auto incrementing primary key.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub auto_increment: bool,
+}
+
+/// ForeignKeySchema is a frozen description of a foreign key constraint in a [TableSchema]. The constraint is named using the NamingConvention of the MigrationManager.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ForeignKeySchema {
+    /// columns are the names of the columns in the table which reference the other table.
+    pub columns: Vec<String>,
+    /// referenced_table is the name of the table which is referenced.
+    pub referenced_table: String,
+    /// referenced_columns are the names of the referenced columns in the other table.
+    pub referenced_columns: Vec<String>,
+    /// on_delete is the action taken when the referenced row is deleted.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "serde_remote::option_foreign_key_action")
+    )]
+    pub on_delete: Option<ForeignKeyAction>,
+    /// on_update is the action taken when the referenced row is updated.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "serde_remote::option_foreign_key_action")
+    )]
+    pub on_update: Option<ForeignKeyAction>,
+}
+
+/// IndexSchema is a frozen description of an index in a [TableSchema]. The index is named using the NamingConvention of the MigrationManager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IndexSchema {
+    /// columns are the names of the indexed columns.
+    pub columns: Vec<String>,
+    /// unique is whether the index is a unique index.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unique: bool,
+}
+
+impl TableSchema {
+    /// from_entity will capture the table `create_table` would create for a SeaORM Entity.
+    pub fn from_entity<E: 'static>(_entity: E) -> Self
+    where
+        E: EntityTrait,
+    {
+        get_table_schema::<E>(|_| true, |_| true)
+    }
+
+    /// column will return the column with the given name.
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// is_primary_key will return whether the column with the given name is part of the primary key of the table.
+    pub fn is_primary_key(&self, name: &str) -> bool {
+        self.primary_key.iter().any(|column| column == name)
+    }
+
+    /// to_rust_source will render the Rust expression which constructs this TableSchema. It uses absolute paths so it can be pasted into a migration without adding any imports.
+    pub fn to_rust_source(&self) -> String {
+        let mut source = String::from("sea_migrations::TableSchema {\n");
+        source += &format!("    name: {:?}.into(),\n", self.name);
+
+        source += "    columns: vec![\n";
+        for column in &self.columns {
+            source += &format!(
+                "        sea_migrations::ColumnSchema {{ name: {:?}.into(), col_type: {}, nullable: {}, default: {}, auto_increment: {} }},\n",
+                column.name,
+                column_type_source(&column.col_type),
+                column.nullable,
+                match &column.default {
+                    Some(default) => format!("Some({})", column_default_source(default)),
+                    None => "None".into(),
+                },
+                column.auto_increment
+            );
+        }
+        source += "    ],\n";
+
+        source += &format!("    primary_key: {},\n", strings_source(&self.primary_key));
+
+        source += "    foreign_keys: vec![\n";
+        for foreign_key in &self.foreign_keys {
+            source += &format!(
+                "        sea_migrations::ForeignKeySchema {{ columns: {}, referenced_table: {:?}.into(), referenced_columns: {}, on_delete: {}, on_update: {} }},\n",
+                strings_source(&foreign_key.columns),
+                foreign_key.referenced_table,
+                strings_source(&foreign_key.referenced_columns),
+                foreign_key_action_source(foreign_key.on_delete),
+                foreign_key_action_source(foreign_key.on_update)
+            );
+        }
+        source += "    ],\n";
+
+        source += "    indexes: vec![\n";
+        for index in &self.indexes {
+            source += &format!(
+                "        sea_migrations::IndexSchema {{ columns: {}, unique: {} }},\n",
+                strings_source(&index.columns),
+                index.unique
+            );
+        }
+        source += "    ],\n";
+
+        source + "}"
+    }
+}
+
+// schema_column returns the column of a TableSchema with the given name or an error if the table has no such column.
+pub(crate) fn schema_column<'a>(
+    table: &'a TableSchema,
+    name: &str,
+) -> Result<&'a ColumnSchema, DbErr> {
+    table.column(name).ok_or_else(|| {
+        DbErr::Custom(format!(
+            "The table schema '{}' has no column '{}'!",
+            table.name, name
+        ))
+    })
+}
+
+// column_type_source returns the Rust expression for a SeaORM ColumnType. The Debug output of most variants is already valid Rust.
+fn column_type_source(col_type: &ColumnType) -> String {
+    match col_type {
+        ColumnType::Custom(name) => format!("sea_orm::ColumnType::Custom({:?}.into())", name),
+        ColumnType::Enum(name, values) => format!(
+            "sea_orm::ColumnType::Enum({:?}.into(), {})",
+            name,
+            strings_source(values)
+        ),
+        col_type => format!("sea_orm::ColumnType::{:?}", col_type),
+    }
+}
+
+// column_default_source returns the Rust expression for a ColumnDefault. Literal values which can't be written as a Rust literal are rendered as the equivalent SQL expression.
+fn column_default_source(default: &ColumnDefault) -> String {
+    let value = match default {
+        ColumnDefault::Value(value) => value,
+        ColumnDefault::Expr(expr) => {
+            return format!("sea_migrations::ColumnDefault::expr({:?})", expr)
+        }
+    };
+    let literal = match value {
+        Value::Bool(Some(v)) => format!("{}", v),
+        Value::TinyInt(Some(v)) => format!("{}i8", v),
+        Value::SmallInt(Some(v)) => format!("{}i16", v),
+        Value::Int(Some(v)) => format!("{}i32", v),
+        Value::BigInt(Some(v)) => format!("{}i64", v),
+        Value::TinyUnsigned(Some(v)) => format!("{}u8", v),
+        Value::SmallUnsigned(Some(v)) => format!("{}u16", v),
+        Value::Unsigned(Some(v)) => format!("{}u32", v),
+        Value::BigUnsigned(Some(v)) => format!("{}u64", v),
+        Value::Float(Some(v)) if v.is_finite() => format!("{:?}f32", v),
+        Value::Double(Some(v)) if v.is_finite() => format!("{:?}f64", v),
+        Value::String(Some(v)) => format!("{:?}", v),
+        _ => {
+            return format!(
+                "sea_migrations::ColumnDefault::expr({:?})",
+                default.to_sql(DbBackend::Sqlite)
+            )
+        }
+    };
+    format!("sea_migrations::ColumnDefault::value({})", literal)
+}
+
+// foreign_key_action_source returns the Rust expression for an optional ForeignKeyAction.
+fn foreign_key_action_source(action: Option<ForeignKeyAction>) -> String {
+    match action {
+        Some(action) => format!("Some(sea_orm::ForeignKeyAction::{:?})", action),
+        None => "None".into(),
+    }
+}
+
+// strings_source returns the Rust expression for a `Vec<String>`.
+fn strings_source(values: &[String]) -> String {
+    format!(
+        "vec![{}]",
+        values
+            .iter()
+            .map(|value| format!("{:?}.into()", value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+// serde_remote contains serde definitions for the SeaORM types used by a TableSchema as they don't implement Serialize and Deserialize themselves.
+#[cfg(feature = "serde")]
+mod serde_remote {
+    use sea_orm::{ColumnType, DbBackend, ForeignKeyAction, Value};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::ColumnDefault;
+
+    // ColumnTypeDef mirrors the variants of the SeaORM ColumnType.
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "ColumnType")]
+    pub enum ColumnTypeDef {
+        Char(Option<u32>),
+        String(Option<u32>),
+        Text,
+        TinyInteger,
+        SmallInteger,
+        Integer,
+        BigInteger,
+        Float,
+        Double,
+        Decimal(Option<(u32, u32)>),
+        DateTime,
+        Timestamp,
+        TimestampWithTimeZone,
+        Time,
+        Date,
+        Binary,
+        Boolean,
+        Money(Option<(u32, u32)>),
+        Json,
+        JsonBinary,
+        Custom(String),
+        Uuid,
+        Enum(String, Vec<String>),
+    }
+
+    // ForeignKeyActionDef mirrors the variants of the sea_query ForeignKeyAction.
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "ForeignKeyAction")]
+    pub enum ForeignKeyActionDef {
+        Restrict,
+        Cascade,
+        SetNull,
+        NoAction,
+        SetDefault,
+    }
+
+    // option_foreign_key_action (de)serializes an optional ForeignKeyAction using ForeignKeyActionDef.
+    pub mod option_foreign_key_action {
+        use super::*;
+
+        #[derive(Serialize, Deserialize)]
+        struct Action(#[serde(with = "ForeignKeyActionDef")] ForeignKeyAction);
+
+        pub fn serialize<S: Serializer>(
+            action: &Option<ForeignKeyAction>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            action.map(Action).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<ForeignKeyAction>, D::Error> {
+            Ok(Option::<Action>::deserialize(deserializer)?.map(|Action(action)| action))
+        }
+    }
+
+    // option_column_default (de)serializes an optional ColumnDefault. Literal values are stored as the matching JSON value (eg. `{"value": 8}`) and values which have no JSON equivalent are stored as the equivalent SQL expression (eg. `{"expr": "X'00'"}`).
+    pub mod option_column_default {
+        use super::*;
+
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        enum ColumnDefaultDef {
+            Value(ValueDef),
+            Expr(String),
+        }
+
+        #[derive(Serialize, Deserialize)]
+        #[serde(untagged)]
+        enum ValueDef {
+            Bool(bool),
+            Int(i64),
+            Unsigned(u64),
+            Float(f64),
+            String(String),
+        }
+
+        pub fn serialize<S: Serializer>(
+            default: &Option<ColumnDefault>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            default
+                .as_ref()
+                .map(|default| {
+                    let value = match default {
+                        ColumnDefault::Value(value) => value,
+                        ColumnDefault::Expr(expr) => return ColumnDefaultDef::Expr(expr.clone()),
+                    };
+                    ColumnDefaultDef::Value(match value {
+                        Value::Bool(Some(v)) => ValueDef::Bool(*v),
+                        Value::TinyInt(Some(v)) => ValueDef::Int(*v as i64),
+                        Value::SmallInt(Some(v)) => ValueDef::Int(*v as i64),
+                        Value::Int(Some(v)) => ValueDef::Int(*v as i64),
+                        Value::BigInt(Some(v)) => ValueDef::Int(*v),
+                        Value::TinyUnsigned(Some(v)) => ValueDef::Unsigned(*v as u64),
+                        Value::SmallUnsigned(Some(v)) => ValueDef::Unsigned(*v as u64),
+                        Value::Unsigned(Some(v)) => ValueDef::Unsigned(*v as u64),
+                        Value::BigUnsigned(Some(v)) => ValueDef::Unsigned(*v),
+                        Value::Float(Some(v)) => ValueDef::Float(*v as f64),
+                        Value::Double(Some(v)) => ValueDef::Float(*v),
+                        Value::String(Some(v)) => ValueDef::String(v.to_string()),
+                        _ => return ColumnDefaultDef::Expr(default.to_sql(DbBackend::Sqlite)),
+                    })
+                })
+                .serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<ColumnDefault>, D::Error> {
+            Ok(Option::<ColumnDefaultDef>::deserialize(deserializer)?.map(
+                |default| match default {
+                    ColumnDefaultDef::Value(ValueDef::Bool(v)) => ColumnDefault::value(v),
+                    ColumnDefaultDef::Value(ValueDef::Int(v)) => ColumnDefault::value(v),
+                    ColumnDefaultDef::Value(ValueDef::Unsigned(v)) => ColumnDefault::value(v),
+                    ColumnDefaultDef::Value(ValueDef::Float(v)) => ColumnDefault::value(v),
+                    ColumnDefaultDef::Value(ValueDef::String(v)) => ColumnDefault::value(v),
+                    ColumnDefaultDef::Expr(expr) => ColumnDefault::Expr(expr),
+                },
+            ))
+        }
+    }
+}
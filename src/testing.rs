@@ -0,0 +1,568 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, DbBackend, DbConn, DbErr, ExecResult,
+    MockDatabase, MockDatabaseConnection, MockDatabaseTrait, MockExecResult, MockRow, QueryResult,
+    Statement, Transaction,
+};
+
+use crate::{
+    introspect_schema, migrations_table::MIGRATIONS_TABLE_NAME, IntrospectedTable, MigrationError,
+    MigrationManager, Migrator, MigratorOptions, MigratorTrait,
+};
+
+/// assert_migrations_reversible will check that the `down` function of every migration undoes its `up` function. It is run against a fresh in-memory SQLite database.
+/// Each migration is applied in order. After its `up` function has run the schema is read, its `down` function is run and the schema must match the schema from before the migration. The schema includes the tables along with their column defaults and check constraints, views, triggers and (on Postgres) enum types. The `up` function is then run again, which must produce the same schema, so that the next migration is checked against the schema it would normally be applied to.
+/// This function panics with a description of what changed if a migration is not reversible so it can be used directly in a test. The `testing` feature must be enabled to use it.
+///
+/// ```rust
+/// use sea_orm::{entity::prelude::*, Database};
+/// use sea_migrations::{Migration, MigrationName, MigrationManager, MigratorTrait};
+/// use sea_migrations::testing::assert_migrations_reversible;
+/// use async_trait::async_trait;
+///
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+/// #[sea_orm(table_name = "cake")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     pub name: String,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+/// pub enum Relation {}
+///
+/// impl ActiveModelBehavior for ActiveModel {}
+///
+/// #[derive(Migration)]
+/// pub struct M20210101020202CreateCake;
+///
+/// #[async_trait]
+/// impl MigratorTrait for M20210101020202CreateCake {
+///     async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+///         mg.create_table(Entity).await?;
+///         Ok(())
+///     }
+///     async fn down(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+///         mg.drop_table(Entity).await?;
+///         Ok(())
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     assert_migrations_reversible(&[Box::new(M20210101020202CreateCake)]).await;
+/// }
+/// ```
+pub async fn assert_migrations_reversible(migrations: &[Box<dyn MigratorTrait>]) {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("sea_migrations: failed to connect to the in-memory SQLite database");
+    assert_migrations_reversible_with_db(&db, migrations).await;
+}
+
+/// assert_migrations_reversible_with_db will check that the `down` function of every migration undoes its `up` function using the provided database connection. The database should be empty as every migration is applied to it and left applied.
+/// Refer to [assert_migrations_reversible] for the details of the checks. SQLite is compared using the SQL of each table, index, view and trigger in `sqlite_master`. Postgres is compared using the check constraints in `pg_constraint`, the enum types in `pg_type`, the views, triggers and column defaults. MySQL is compared using the check constraints, views, triggers and column defaults in information_schema.
+///
+/// ```rust,should_panic
+/// use sea_orm::{Database, DbErr};
+/// use sea_orm::sea_query::{Alias, Expr, Query};
+/// use sea_migrations::{Migration, MigrationName, MigrationManager, MigratorTrait};
+/// use sea_migrations::testing::assert_migrations_reversible_with_db;
+/// use async_trait::async_trait;
+///
+/// #[derive(Migration)]
+/// pub struct M20210101020202CreateCakeView;
+///
+/// #[async_trait]
+/// impl MigratorTrait for M20210101020202CreateCakeView {
+///     async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+///         let cake_names = Query::select().expr_as(Expr::val("Sponge"), Alias::new("name")).to_owned();
+///         mg.create_view(Alias::new("cake_names"), cake_names).await?;
+///         Ok(())
+///     }
+///     async fn down(&self, _mg: &MigrationManager) -> Result<(), DbErr> {
+///         Ok(()) // The view isn't dropped so this panics.
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let db = Database::connect("sqlite::memory:").await.unwrap();
+///     assert_migrations_reversible_with_db(&db, &[Box::new(M20210101020202CreateCakeView)]).await;
+/// }
+/// ```
+pub async fn assert_migrations_reversible_with_db(
+    db: &DbConn,
+    migrations: &[Box<dyn MigratorTrait>],
+) {
+    let mg = MigrationManager::new(db);
+
+    for migration in migrations {
+        let name = migration.name();
+        let before = snapshot(db, name).await;
+
+        migration.up(&mg).await.unwrap_or_else(|err| {
+            panic!(
+                "sea_migrations: migration '{}' failed to apply: {}",
+                name, err
+            )
+        });
+        let applied = snapshot(db, name).await;
+
+        migration.down(&mg).await.unwrap_or_else(|err| {
+            panic!(
+                "sea_migrations: migration '{}' failed to undo: {}",
+                name, err
+            )
+        });
+        let reverted = snapshot(db, name).await;
+        if reverted != before {
+            panic!(
+                "sea_migrations: the down function of migration '{}' didn't restore the schema:\n{}",
+                name,
+                describe_changes(&before, &reverted)
+            );
+        }
+
+        migration.up(&mg).await.unwrap_or_else(|err| {
+            panic!(
+                "sea_migrations: migration '{}' failed to reapply after being undone: {}",
+                name, err
+            )
+        });
+        let reapplied = snapshot(db, name).await;
+        if reapplied != applied {
+            panic!(
+                "sea_migrations: migration '{}' created a different schema when it was reapplied after being undone:\n{}",
+                name,
+                describe_changes(&applied, &reapplied)
+            );
+        }
+    }
+}
+
+// Snapshot is the schema of the database compared by `assert_migrations_reversible_with_db`.
+#[derive(Debug, PartialEq, Eq)]
+struct Snapshot {
+    tables: Vec<IntrospectedTable>,
+    // objects maps the kind and name of each other database object (eg. `("view", "cake_names")`) to its definition.
+    objects: BTreeMap<(String, String), String>,
+}
+
+// snapshot reads the schema of the database and panics if it can't be read.
+async fn snapshot(db: &DbConn, migration_name: &str) -> Snapshot {
+    let read = async {
+        Ok::<_, DbErr>(Snapshot {
+            tables: introspect_schema(db).await?,
+            objects: schema_objects(db).await?,
+        })
+    };
+    read.await.unwrap_or_else(|err| {
+        panic!(
+            "sea_migrations: failed to read the schema while checking migration '{}': {}",
+            migration_name, err
+        )
+    })
+}
+
+// schema_objects reads the definitions of the database objects which `introspect_schema` doesn't describe. Each query returns the `kind`, `name` and `definition` of the objects.
+async fn schema_objects(db: &DbConn) -> Result<BTreeMap<(String, String), String>, DbErr> {
+    let backend = db.get_database_backend();
+    let queries: &[&str] = match backend {
+        DbBackend::Postgres => &[
+            "SELECT 'check' AS kind, tbl.relname || '.' || con.conname AS name, pg_get_constraintdef(con.oid) AS definition FROM pg_constraint con JOIN pg_class tbl ON tbl.oid = con.conrelid JOIN pg_namespace n ON n.oid = tbl.relnamespace WHERE con.contype = 'c' AND n.nspname = current_schema()",
+            "SELECT 'enum' AS kind, t.typname AS name, string_agg(e.enumlabel, ',' ORDER BY e.enumsortorder) AS definition FROM pg_type t JOIN pg_enum e ON e.enumtypid = t.oid JOIN pg_namespace n ON n.oid = t.typnamespace WHERE n.nspname = current_schema() GROUP BY t.typname",
+            "SELECT 'view' AS kind, viewname AS name, definition FROM pg_views WHERE schemaname = current_schema()",
+            "SELECT 'materialized view' AS kind, matviewname AS name, definition FROM pg_matviews WHERE schemaname = current_schema()",
+            "SELECT 'trigger' AS kind, c.relname || '.' || t.tgname AS name, pg_get_triggerdef(t.oid) AS definition FROM pg_trigger t JOIN pg_class c ON c.oid = t.tgrelid JOIN pg_namespace n ON n.oid = c.relnamespace WHERE NOT t.tgisinternal AND n.nspname = current_schema()",
+            "SELECT 'default' AS kind, c.relname || '.' || a.attname AS name, pg_get_expr(d.adbin, d.adrelid) AS definition FROM pg_attrdef d JOIN pg_class c ON c.oid = d.adrelid JOIN pg_attribute a ON a.attrelid = d.adrelid AND a.attnum = d.adnum JOIN pg_namespace n ON n.oid = c.relnamespace WHERE n.nspname = current_schema()",
+        ],
+        DbBackend::MySql => &[
+            "SELECT 'check' AS kind, CONCAT(tc.table_name, '.', cc.constraint_name) AS name, cc.check_clause AS definition FROM information_schema.check_constraints cc JOIN information_schema.table_constraints tc ON tc.constraint_schema = cc.constraint_schema AND tc.constraint_name = cc.constraint_name WHERE cc.constraint_schema = DATABASE() AND tc.constraint_type = 'CHECK'",
+            "SELECT 'view' AS kind, table_name AS name, view_definition AS definition FROM information_schema.views WHERE table_schema = DATABASE()",
+            "SELECT 'trigger' AS kind, CONCAT(event_object_table, '.', trigger_name) AS name, CONCAT(action_timing, ' ', event_manipulation, ' ', action_statement) AS definition FROM information_schema.triggers WHERE trigger_schema = DATABASE()",
+            "SELECT 'default' AS kind, CONCAT(table_name, '.', column_name) AS name, column_default AS definition FROM information_schema.columns WHERE table_schema = DATABASE() AND column_default IS NOT NULL",
+        ],
+        // The SQL of a table includes its column defaults and check constraints.
+        DbBackend::Sqlite => &["SELECT type AS kind, name, sql AS definition FROM sqlite_master WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'"],
+    };
+
+    let mut objects = BTreeMap::new();
+    for sql in queries {
+        for row in db
+            .query_all(Statement::from_string(backend, sql.to_string()))
+            .await?
+        {
+            let name: String = row.try_get("", "name")?;
+            if name == MIGRATIONS_TABLE_NAME
+                || name.starts_with(&format!("{}.", MIGRATIONS_TABLE_NAME))
+            {
+                continue;
+            }
+            let definition: String = row.try_get("", "definition")?;
+            let definition = match backend {
+                DbBackend::Sqlite => normalize_sqlite_sql(&definition),
+                _ => definition,
+            };
+            objects.insert((row.try_get("", "kind")?, name), definition);
+        }
+    }
+
+    Ok(objects)
+}
+
+// normalize_sqlite_sql removes the differences in quoting and whitespace from the SQL stored in `sqlite_master`. SQLite stores the SQL a table was created with and edits it for `ALTER TABLE` so a table which was rebuilt has the same definition written differently.
+fn normalize_sqlite_sql(sql: &str) -> String {
+    let sql = sql
+        .replace(['"', '`'], "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    sql.replace("( ", "(").replace(" )", ")").replace(" ,", ",")
+}
+
+// describe_changes lists the tables and other database objects which were added, removed or changed between two schemas.
+fn describe_changes(expected: &Snapshot, actual: &Snapshot) -> String {
+    let mut changes = Vec::new();
+    for table in &expected.tables {
+        match actual.tables.iter().find(|t| t.name == table.name) {
+            None => changes.push(format!("  table '{}' is missing", table.name)),
+            Some(actual_table) if actual_table != table => changes.push(format!(
+                "  table '{}' changed\n    expected: {:?}\n    actual:   {:?}",
+                table.name, table, actual_table
+            )),
+            Some(_) => {}
+        }
+    }
+    for table in &actual.tables {
+        if !expected.tables.iter().any(|t| t.name == table.name) {
+            changes.push(format!("  table '{}' was not dropped", table.name));
+        }
+    }
+
+    for ((kind, name), definition) in &expected.objects {
+        match actual.objects.get(&(kind.clone(), name.clone())) {
+            None => changes.push(format!("  {} '{}' is missing", kind, name)),
+            Some(actual_definition) if actual_definition != definition => changes.push(format!(
+                "  {} '{}' changed\n    expected: {}\n    actual:   {}",
+                kind, name, definition, actual_definition
+            )),
+            Some(_) => {}
+        }
+    }
+    for (kind, name) in actual.objects.keys() {
+        if !expected.objects.contains_key(&(kind.clone(), name.clone())) {
+            changes.push(format!("  {} '{}' was not dropped", kind, name));
+        }
+    }
+    changes.join("\n")
+}
+
+/// migrated_schema_snapshot will run all of the migrations on a fresh in-memory SQLite database and render the resulting schema using [render_schema]. This is designed to be compared against a snapshot file (eg. using `insta`) so changes to the schema show up as a diff of one text file when reviewing a pull request.
+///
+/// ```rust
+/// use sea_orm::{entity::prelude::*, Database};
+/// use sea_migrations::{Migration, MigrationName, MigrationManager, MigratorTrait};
+/// use sea_migrations::testing::migrated_schema_snapshot;
+/// use async_trait::async_trait;
+///
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+/// #[sea_orm(table_name = "cake")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     #[sea_orm(unique)]
+///     pub name: String,
+///     pub description: Option<String>,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+/// pub enum Relation {}
+///
+/// impl ActiveModelBehavior for ActiveModel {}
+///
+/// #[derive(Migration)]
+/// pub struct M20210101020202CreateCake;
+///
+/// #[async_trait]
+/// impl MigratorTrait for M20210101020202CreateCake {
+///     async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+///         mg.create_table(Entity).await?;
+///         Ok(())
+///     }
+///     async fn down(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+///         mg.drop_table(Entity).await?;
+///         Ok(())
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let snapshot = migrated_schema_snapshot(&mut vec![Box::new(M20210101020202CreateCake)]).await;
+///     assert_eq!(
+///         snapshot,
+///         "-- sqlite
+///
+/// table cake
+///   column id INTEGER NOT NULL
+///   column name TEXT NOT NULL
+///   column description TEXT
+///   unique index uq_cake_name (name)
+/// "
+///     );
+/// }
+/// ```
+pub async fn migrated_schema_snapshot(migrations: &mut Vec<Box<dyn MigratorTrait>>) -> String {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("sea_migrations: failed to connect to the in-memory SQLite database");
+    migrated_schema_snapshot_with_db(&db, migrations).await
+}
+
+/// migrated_schema_snapshot_with_db will run all of the migrations on the provided database connection and render the resulting schema using [render_schema]. The database should be empty so the snapshot only depends on the migrations.
+pub async fn migrated_schema_snapshot_with_db(
+    db: &DbConn,
+    migrations: &mut Vec<Box<dyn MigratorTrait>>,
+) -> String {
+    Migrator::run(db, migrations)
+        .await
+        .unwrap_or_else(|err| panic!("sea_migrations: failed to run the migrations: {}", err));
+    let tables = introspect_schema(db)
+        .await
+        .unwrap_or_else(|err| panic!("sea_migrations: failed to read the schema: {}", err));
+    render_schema(db.get_database_backend(), &tables)
+}
+
+/// render_schema will render the tables read using [introspect_schema] as text. The output starts with the database backend as the column types are specific to it.
+/// Tables are ordered by name and columns in the order they are defined. Indexes and foreign keys are ordered by their columns so the output doesn't depend on the order they were created in.
+pub fn render_schema(backend: DbBackend, tables: &[IntrospectedTable]) -> String {
+    let mut output = match backend {
+        DbBackend::Postgres => "-- postgres\n",
+        DbBackend::MySql => "-- mysql\n",
+        DbBackend::Sqlite => "-- sqlite\n",
+    }
+    .to_string();
+
+    for table in tables {
+        output += &format!("\ntable {}\n", table.name);
+        for column in &table.columns {
+            output += &format!(
+                "  column {} {}{}\n",
+                column.name,
+                column.data_type,
+                if column.nullable { "" } else { " NOT NULL" }
+            );
+        }
+
+        let mut indexes = table.indexes.iter().collect::<Vec<_>>();
+        indexes.sort_by(|a, b| (&a.columns, &a.name).cmp(&(&b.columns, &b.name)));
+        for index in indexes {
+            output += &format!(
+                "  {} {} ({})\n",
+                if index.unique {
+                    "unique index"
+                } else {
+                    "index"
+                },
+                index.name,
+                index.columns.join(", ")
+            );
+        }
+
+        let mut foreign_keys = table.foreign_keys.iter().collect::<Vec<_>>();
+        foreign_keys.sort_by(|a, b| {
+            (&a.columns, &a.referenced_table, &a.name).cmp(&(
+                &b.columns,
+                &b.referenced_table,
+                &b.name,
+            ))
+        });
+        for foreign_key in foreign_keys {
+            output += "  foreign key ";
+            if let Some(name) = &foreign_key.name {
+                output += &format!("{} ", name);
+            }
+            output += &format!(
+                "({}) references {} ({})\n",
+                foreign_key.columns.join(", "),
+                foreign_key.referenced_table,
+                foreign_key.referenced_columns.join(", ")
+            );
+        }
+    }
+
+    output
+}
+
+/// mock_database will create a connection to a SeaORM MockDatabase for the database backend. Every statement run against it succeeds and reports that it changed one row and every query returns no rows so it looks like an empty database to the migrations.
+/// The statements which were run can be read using `into_transaction_log` on the connection. This allows testing the SQL generated for Postgres and MySQL without running those servers.
+pub fn mock_database(backend: DbBackend) -> DbConn {
+    DatabaseConnection::MockDatabaseConnection(Arc::new(MockDatabaseConnection::new(
+        EmptyMockDatabase {
+            db: Some(MockDatabase::new(backend)),
+        },
+    )))
+}
+
+/// mock_run will run all of the migrations using [Migrator::run_with_options] against a [mock_database] for the database backend and return the transaction log. This includes the statements used to manage the migrations table.
+/// The lock on the migrations table is taken with a fixed token on a mock database so the log is the same every time and can be compared against a snapshot.
+///
+/// ```rust
+/// use sea_orm::{DbBackend, Statement, Transaction};
+/// use sea_migrations::{MigrationError, MigratorTrait, SqlMigration};
+/// use sea_migrations::testing::mock_run;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), MigrationError> {
+///     let migrations = || -> Vec<Box<dyn MigratorTrait>> {
+///         vec![Box::new(SqlMigration::new(
+///             "M20210101020202CreateBakery",
+///             "CREATE TABLE bakery (id serial PRIMARY KEY, name varchar NOT NULL)",
+///             Some("DROP TABLE bakery"),
+///         ))]
+///     };
+///
+///     let log = mock_run(DbBackend::Postgres, &mut migrations()).await?;
+///     assert_eq!(log, mock_run(DbBackend::Postgres, &mut migrations()).await?);
+///     assert!(log.contains(&Transaction::one(Statement::from_string(
+///         DbBackend::Postgres,
+///         "CREATE TABLE bakery (id serial PRIMARY KEY, name varchar NOT NULL)".into(),
+///     ))));
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn mock_run(
+    backend: DbBackend,
+    migrations: &mut Vec<Box<dyn MigratorTrait>>,
+) -> Result<Vec<Transaction>, MigrationError> {
+    let db = mock_database(backend);
+    Migrator::run_with_options(&db, migrations, MigratorOptions::default()).await?;
+    Ok(db.into_transaction_log())
+}
+
+/// mock_up will run the `up` function of a single migration against a [mock_database] for the database backend and return the transaction log.
+///
+/// ```rust
+/// use sea_orm::{entity::prelude::*, DbBackend, Statement, Transaction};
+/// use sea_migrations::{Migration, MigrationName, MigrationManager, MigratorTrait};
+/// use sea_migrations::testing::mock_up;
+/// use async_trait::async_trait;
+///
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+/// #[sea_orm(table_name = "cake")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     pub name: String,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+/// pub enum Relation {}
+///
+/// impl ActiveModelBehavior for ActiveModel {}
+///
+/// #[derive(Migration)]
+/// pub struct M20210101020202CreateCake;
+///
+/// #[async_trait]
+/// impl MigratorTrait for M20210101020202CreateCake {
+///     async fn up(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+///         mg.create_table(Entity).await?;
+///         Ok(())
+///     }
+///     async fn down(&self, mg: &MigrationManager) -> Result<(), DbErr> {
+///         mg.drop_table(Entity).await?;
+///         Ok(())
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DbErr> {
+///     assert_eq!(
+///         mock_up(DbBackend::Postgres, &M20210101020202CreateCake).await?,
+///         vec![
+///             Transaction::one(Statement::from_string(
+///                 DbBackend::Postgres,
+///                 r#"CREATE TABLE IF NOT EXISTS "cake" ( "id" serial NOT NULL PRIMARY KEY, "name" varchar NOT NULL )"#.into(),
+///             )),
+///         ]
+///     );
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn mock_up(
+    backend: DbBackend,
+    migration: &dyn MigratorTrait,
+) -> Result<Vec<Transaction>, DbErr> {
+    let db = mock_database(backend);
+    migration.up(&MigrationManager::new(&db)).await?;
+    Ok(db.into_transaction_log())
+}
+
+/// mock_down will run the `down` function of a single migration against a [mock_database] for the database backend and return the transaction log.
+pub async fn mock_down(
+    backend: DbBackend,
+    migration: &dyn MigratorTrait,
+) -> Result<Vec<Transaction>, DbErr> {
+    let db = mock_database(backend);
+    migration.down(&MigrationManager::new(&db)).await?;
+    Ok(db.into_transaction_log())
+}
+
+// EmptyMockDatabase wraps a MockDatabase and provides it with a result before every statement so it never runs out of results.
+#[derive(Debug)]
+struct EmptyMockDatabase {
+    db: Option<MockDatabase>,
+}
+
+impl EmptyMockDatabase {
+    // db returns the wrapped MockDatabase after updating it using the provided function.
+    fn db(&mut self, update: impl FnOnce(MockDatabase) -> MockDatabase) -> &mut MockDatabase {
+        let db = update(self.db.take().expect("the mock database is always present"));
+        self.db.insert(db)
+    }
+}
+
+impl MockDatabaseTrait for EmptyMockDatabase {
+    fn execute(&mut self, counter: usize, stmt: Statement) -> Result<ExecResult, DbErr> {
+        // Every statement reports that it changed a row so refreshing the lock on the migrations table succeeds.
+        self.db(|db| {
+            db.append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+        })
+        .execute(counter, stmt)
+    }
+
+    fn query(&mut self, counter: usize, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.db(|db| db.append_query_results(vec![Vec::<MockRow>::new()]))
+            .query(counter, stmt)
+    }
+
+    fn begin(&mut self) {
+        self.db(|db| db).begin()
+    }
+
+    fn commit(&mut self) {
+        self.db(|db| db).commit()
+    }
+
+    fn rollback(&mut self) {
+        self.db(|db| db).rollback()
+    }
+
+    fn drain_transaction_log(&mut self) -> Vec<Transaction> {
+        self.db(|db| db).drain_transaction_log()
+    }
+
+    fn get_database_backend(&self) -> DbBackend {
+        self.db
+            .as_ref()
+            .expect("the mock database is always present")
+            .get_database_backend()
+    }
+}
@@ -0,0 +1,40 @@
+/// TriggerTiming controls whether a trigger runs before or after the change which caused it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerTiming {
+    /// Before runs the trigger before the row is changed.
+    Before,
+    /// After runs the trigger after the row is changed.
+    After,
+}
+
+impl TriggerTiming {
+    // as_sql returns the SQL keyword for the timing.
+    pub(crate) fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Before => "BEFORE",
+            Self::After => "AFTER",
+        }
+    }
+}
+
+/// TriggerEvent is the change to a row which causes a trigger to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEvent {
+    /// Insert runs the trigger when a row is inserted.
+    Insert,
+    /// Update runs the trigger when a row is updated.
+    Update,
+    /// Delete runs the trigger when a row is deleted.
+    Delete,
+}
+
+impl TriggerEvent {
+    // as_sql returns the SQL keyword for the event.
+    pub(crate) fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Insert => "INSERT",
+            Self::Update => "UPDATE",
+            Self::Delete => "DELETE",
+        }
+    }
+}